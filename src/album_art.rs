@@ -0,0 +1,133 @@
+// src/album_art.rs
+//! On-disk cache of album art for the phone's now-playing track.
+//!
+//! Art is stored under `$XDG_CACHE_HOME/cosmic-connect/<device>/album-art`,
+//! one file per track, so switching back to a track doesn't fetch it again.
+//! Only the art of the most recent tracks is kept. Art the phone sends as a
+//! link is downloaded with `curl`.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Tracks whose art is kept per device
+const CACHE_LIMIT: usize = 50;
+
+/// Directory holding the cached art of one device
+pub fn cache_dir(device_id: &str) -> PathBuf {
+    let base = std::env::var("XDG_CACHE_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            PathBuf::from(home).join(".cache")
+        });
+
+    base.join("cosmic-connect").join(device_id).join("album-art")
+}
+
+/// Return a local copy of the art at `url` for the given track, fetching it
+/// on the first request.
+///
+/// `url` is what the phone reported: a `file://` URL or path for art that
+/// kdeconnectd already downloaded, or an `http(s)://` URL.
+pub async fn fetch(device_id: &str, track: &[&str], url: &str) -> Option<PathBuf> {
+    if url.is_empty() {
+        return None;
+    }
+
+    let dir = cache_dir(device_id);
+    let path = dir.join(format!("{:016x}", track_key(track)));
+    if tokio::fs::metadata(&path).await.is_ok() {
+        return Some(path);
+    }
+
+    if let Err(e) = tokio::fs::create_dir_all(&dir).await {
+        eprintln!("Failed to create album art cache {}: {}", dir.display(), e);
+        return None;
+    }
+
+    // Write next to the final file and rename, so a half-written file is never used
+    let partial = path.with_extension("part");
+    let fetched = if url.starts_with("http://") || url.starts_with("https://") {
+        download(url, &partial).await
+    } else {
+        let source = url.strip_prefix("file://").unwrap_or(url);
+        let source = urlencoding::decode(source).map(|s| s.into_owned()).unwrap_or_else(|_| source.to_string());
+        tokio::fs::copy(&source, &partial).await.is_ok()
+    };
+
+    if fetched && tokio::fs::rename(&partial, &path).await.is_ok() {
+        eprintln!("Cached album art at {}", path.display());
+        prune(&dir).await;
+        Some(path)
+    } else {
+        let _ = tokio::fs::remove_file(&partial).await;
+        eprintln!("Failed to fetch album art from {}", url);
+        None
+    }
+}
+
+/// Cache key for a track, from whatever identifies it (player, artist, album, title)
+///
+/// 64-bit FNV-1a, which unlike the standard library's hasher gives the same
+/// key across Rust releases, so the cache outlives a toolchain update.
+fn track_key(track: &[&str]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    // Each part ends with a 0xff byte, which UTF-8 never contains, so
+    // ("ab", "c") and ("a", "bc") differ
+    track.iter()
+        .flat_map(|part| part.bytes().chain([0xff]))
+        .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
+
+/// Remove all but the `CACHE_LIMIT` most recently fetched files
+async fn prune(dir: &Path) {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return;
+    };
+    let mut files = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        // Another fetch may still be writing its partial file
+        if path.extension().is_some() {
+            continue;
+        }
+        let modified = match entry.metadata().await {
+            Ok(metadata) => metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            Err(_) => continue,
+        };
+        files.push((modified, path));
+    }
+
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, path) in files.into_iter().skip(CACHE_LIMIT) {
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+}
+
+async fn download(url: &str, destination: &Path) -> bool {
+    tokio::process::Command::new("curl")
+        .args(["--silent", "--fail", "--location", "--max-time", "10", "--output"])
+        .arg(destination)
+        .arg(url)
+        .status()
+        .await
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_key() {
+        // Cached files are named after the key; it must never change
+        assert_eq!(track_key(&[]), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(track_key(&["a"]), 0x089b_c907_b544_c769);
+        assert_ne!(track_key(&["ab", "c"]), track_key(&["a", "bc"]));
+    }
+}
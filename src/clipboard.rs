@@ -0,0 +1,194 @@
+// src/clipboard.rs
+//! The local clipboard, and a history of its text to send to devices again.
+//!
+//! The clipboard is read and written through the applet's own Wayland
+//! connection, which only sees the current selection while one of its
+//! surfaces has keyboard focus, such as the open popup.
+//!
+//! kdeconnectd has no D-Bus signal for clipboard content received from a
+//! device; it puts it straight into the local clipboard, so it shows up here
+//! like anything copied locally. The history is kept in memory only, since
+//! clipboards hold things that shouldn't end up on disk.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cosmic::iced::clipboard::mime::AllowedMimeTypes;
+use cosmic::iced::Task;
+
+/// MIME type password managers add to the secrets they copy
+pub const PASSWORD_HINT: &str = "x-kde-passwordManagerHint";
+
+/// Items kept in the history
+pub const HISTORY_LIMIT: usize = 20;
+
+/// Saved clipboard images kept on disk; older ones have long been sent
+const SAVED_IMAGE_LIMIT: usize = 10;
+
+/// Text types in order of preference; images are only used when there's no text
+pub(crate) const TEXT: [&str; 4] = ["text/plain;charset=utf-8", "text/plain", "UTF8_STRING", "STRING"];
+const IMAGES: [&str; 5] = ["image/png", "image/jpeg", "image/webp", "image/gif", "image/bmp"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardItem {
+    pub text: String,
+    /// Copied from a password manager
+    pub is_password: bool,
+    /// Seconds since the Unix epoch
+    pub copied_at: u64,
+}
+
+/// A picture in the clipboard
+#[derive(Debug, Clone)]
+pub struct ClipboardImage {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// What the local clipboard holds
+#[derive(Debug, Clone)]
+pub enum ClipboardContent {
+    Text(ClipboardItem),
+    Image(ClipboardImage),
+}
+
+/// Clipboard data as offered, before the password hint is known
+enum Offered {
+    Text(String),
+    Image(ClipboardImage),
+}
+
+impl AllowedMimeTypes for Offered {
+    fn allowed() -> Cow<'static, [String]> {
+        Cow::Owned(TEXT.into_iter().chain(IMAGES).map(str::to_string).collect())
+    }
+}
+
+impl TryFrom<(Vec<u8>, String)> for Offered {
+    type Error = String;
+
+    fn try_from((data, mime_type): (Vec<u8>, String)) -> Result<Self, Self::Error> {
+        if mime_type.starts_with("image/") {
+            return Ok(Self::Image(ClipboardImage { mime_type, data }));
+        }
+
+        let text = String::from_utf8(data).map_err(|e| e.to_string())?;
+        if text.is_empty() {
+            return Err("Clipboard text is empty".to_string());
+        }
+        Ok(Self::Text(text))
+    }
+}
+
+/// Present when the clipboard carries the password manager hint
+struct PasswordHint;
+
+impl AllowedMimeTypes for PasswordHint {
+    fn allowed() -> Cow<'static, [String]> {
+        Cow::Owned(vec![PASSWORD_HINT.to_string()])
+    }
+}
+
+impl TryFrom<(Vec<u8>, String)> for PasswordHint {
+    type Error = String;
+
+    fn try_from(_: (Vec<u8>, String)) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+}
+
+/// Read the local clipboard, `None` when it is empty or holds neither text
+/// nor a picture
+pub fn read() -> Task<Option<ClipboardContent>> {
+    cosmic::iced::clipboard::read_data::<PasswordHint>().then(|hint| {
+        let is_password = hint.is_some();
+        cosmic::iced::clipboard::read_data::<Offered>().map(move |offered| {
+            offered.map(|offered| match offered {
+                Offered::Text(text) => ClipboardContent::Text(ClipboardItem {
+                    text,
+                    is_password,
+                    copied_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+                }),
+                Offered::Image(image) => ClipboardContent::Image(image),
+            })
+        })
+    })
+}
+
+/// Put text in the local clipboard
+pub fn write<T>(text: String) -> Task<T> {
+    cosmic::iced::clipboard::write(text)
+}
+
+/// Save a clipboard picture to send it as a file
+///
+/// Pictures go to `$XDG_CACHE_HOME/cosmic-connect/clipboard`, where only the
+/// last few are kept.
+pub fn save_image(image: &ClipboardImage) -> io::Result<PathBuf> {
+    let base = std::env::var("XDG_CACHE_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            PathBuf::from(home).join(".cache")
+        });
+    let dir = base.join("cosmic-connect").join("clipboard");
+    std::fs::create_dir_all(&dir)?;
+    prune_images(&dir);
+
+    let extension = match image.mime_type.as_str() {
+        "image/jpeg" => "jpg",
+        other => other.trim_start_matches("image/"),
+    };
+    let copied_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    // Two images copied in the same millisecond, or by two applet instances,
+    // must not overwrite each other while kdeconnectd is still sending one
+    for attempt in 0u32.. {
+        let name = match attempt {
+            0 => format!("clipboard-{}.{}", copied_at, extension),
+            n => format!("clipboard-{}-{}.{}", copied_at, n, extension),
+        };
+        let path = dir.join(name);
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(&image.data)?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!()
+}
+
+/// Remove all but the newest saved images, making room for one more
+fn prune_images(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().unwrap_or(UNIX_EPOCH);
+            Some((modified, entry.path()))
+        })
+        .collect();
+
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, path) in files.into_iter().skip(SAVED_IMAGE_LIMIT - 1) {
+        let _ = std::fs::remove_file(&path);
+    }
+}
+
+/// Move `item` to the front of the history. Passwords are never kept.
+pub fn remember(history: &mut Vec<ClipboardItem>, item: ClipboardItem) {
+    if item.is_password {
+        return;
+    }
+    history.retain(|old| old.text != item.text);
+    history.insert(0, item);
+    history.truncate(HISTORY_LIMIT);
+}
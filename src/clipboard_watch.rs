@@ -0,0 +1,199 @@
+// src/clipboard_watch.rs
+//! Watching the local clipboard in the background, to send copies to devices
+//! that share automatically and keep the clipboard history current while the
+//! popup is closed.
+//!
+//! The applet's own clipboard access only works while the popup has focus,
+//! so changes are followed over the wlr data-control protocol on a separate
+//! Wayland connection. COSMIC only offers it with
+//! `COSMIC_DATA_CONTROL_ENABLED=1` set for the compositor.
+
+use std::io::Read;
+use std::os::fd::AsFd;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::Stream;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry, wl_seat::WlSeat};
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols_wlr::data_control::v1::client::{
+    zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+    zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+    zwlr_data_control_offer_v1::{self, ZwlrDataControlOfferV1},
+};
+
+use crate::clipboard::{ClipboardItem, PASSWORD_HINT, TEXT};
+
+/// Copying often replaces the selection several times in a row
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Text copied locally, debounced, for an iced subscription.
+///
+/// Ends right away if the compositor doesn't offer data control.
+pub fn watch() -> impl Stream<Item = ClipboardItem> {
+    async_stream::stream! {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        std::thread::spawn(move || {
+            if let Err(e) = run(tx) {
+                eprintln!("Not watching the clipboard: {}", e);
+            }
+        });
+
+        while let Some(mut item) = rx.recv().await {
+            while let Ok(Some(newer)) = tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                item = newer;
+            }
+            yield item;
+        }
+    }
+}
+
+struct Watcher {
+    tx: UnboundedSender<ClipboardItem>,
+    /// The offer that is the current selection
+    selection: Option<ZwlrDataControlOfferV1>,
+    /// The compositor stopped sending selections
+    finished: bool,
+}
+
+/// Follow the selection until the stream is dropped or the connection fails
+fn run(tx: UnboundedSender<ClipboardItem>) -> Result<(), String> {
+    let conn = Connection::connect_to_env().map_err(|e| e.to_string())?;
+    let (globals, mut queue) = registry_queue_init::<Watcher>(&conn).map_err(|e| e.to_string())?;
+    let qh = queue.handle();
+
+    let manager: ZwlrDataControlManagerV1 = globals
+        .bind(&qh, 1..=2, ())
+        .map_err(|_| "the compositor doesn't offer wlr data control".to_string())?;
+    let seat: WlSeat = globals.bind(&qh, 1..=1, ()).map_err(|e| e.to_string())?;
+    let _device = manager.get_data_device(&seat, &qh, ());
+
+    let mut watcher = Watcher { tx, selection: None, finished: false };
+    while !watcher.tx.is_closed() {
+        queue.blocking_dispatch(&mut watcher).map_err(|e| e.to_string())?;
+        if watcher.finished {
+            return Err("the compositor ended clipboard data control".to_string());
+        }
+    }
+    Ok(())
+}
+
+impl Watcher {
+    fn selection_changed(&mut self, offer: Option<ZwlrDataControlOfferV1>, conn: &Connection) {
+        if let Some(old) = self.selection.take() {
+            old.destroy();
+        }
+        let Some(offer) = offer else {
+            return;
+        };
+
+        let mime_types = offer
+            .data::<Mutex<Vec<String>>>()
+            .and_then(|types| types.lock().ok().map(|types| types.clone()))
+            .unwrap_or_default();
+        let is_password = mime_types.iter().any(|mime| mime == PASSWORD_HINT);
+
+        if let Some(mime) = TEXT.iter().find(|text| mime_types.iter().any(|mime| mime == *text)) {
+            match std::io::pipe() {
+                Ok((mut reader, writer)) => {
+                    offer.receive(mime.to_string(), writer.as_fd());
+                    // Our copy of the write end must close for the read to end
+                    drop(writer);
+                    let _ = conn.flush();
+
+                    // The copying app writes at its own pace; don't hold up the queue
+                    let tx = self.tx.clone();
+                    std::thread::spawn(move || {
+                        let mut data = Vec::new();
+                        if reader.read_to_end(&mut data).is_err() {
+                            return;
+                        }
+                        match String::from_utf8(data) {
+                            Ok(text) if !text.is_empty() => {
+                                let _ = tx.send(ClipboardItem {
+                                    text,
+                                    is_password,
+                                    copied_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+                                });
+                            }
+                            _ => {}
+                        }
+                    });
+                }
+                Err(e) => eprintln!("✗ Can't read the clipboard: {}", e),
+            }
+        }
+
+        self.selection = Some(offer);
+    }
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for Watcher {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for Watcher {
+    fn event(_: &mut Self, _: &WlSeat, _: <WlSeat as Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<ZwlrDataControlManagerV1, ()> for Watcher {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrDataControlManagerV1,
+        _: <ZwlrDataControlManagerV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrDataControlDeviceV1, ()> for Watcher {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrDataControlDeviceV1,
+        event: zwlr_data_control_device_v1::Event,
+        _: &(),
+        conn: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_data_control_device_v1::Event::Selection { id } => state.selection_changed(id, conn),
+            // Middle-click selections aren't synced
+            zwlr_data_control_device_v1::Event::PrimarySelection { id: Some(offer) } => offer.destroy(),
+            zwlr_data_control_device_v1::Event::Finished => state.finished = true,
+            _ => {}
+        }
+    }
+
+    event_created_child!(Watcher, ZwlrDataControlDeviceV1, [
+        zwlr_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ZwlrDataControlOfferV1, Mutex::new(Vec::new())),
+    ]);
+}
+
+impl Dispatch<ZwlrDataControlOfferV1, Mutex<Vec<String>>> for Watcher {
+    fn event(
+        _: &mut Self,
+        _: &ZwlrDataControlOfferV1,
+        event: zwlr_data_control_offer_v1::Event,
+        mime_types: &Mutex<Vec<String>>,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_data_control_offer_v1::Event::Offer { mime_type } = event {
+            if let Ok(mut types) = mime_types.lock() {
+                types.push(mime_type);
+            }
+        }
+    }
+}
@@ -1,9 +1,29 @@
 // src/dbus.rs
-use crate::models::Device;
+use crate::album_art;
+use crate::models::{Device, PairState, Plugin, PluginSet};
+use crate::proxies::{
+    self, BatteryProxy, ClipboardProxy, ConnectivityReportProxy, DeviceObject, DeviceProxy,
+    FindMyPhoneProxy, LockDeviceProxy, MediaPlayer2Proxy, MprisRemoteProxy, NotificationsProxy,
+    PingProxy, RemoteControlProxy, RemoteKeyboardProxy, ShareProxy, VirtualMonitorProxy,
+};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Mutex;
+use tokio::time::Duration;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::Value;
 use zbus::Connection;
 
+/// How many devices `fetch_devices` queries at the same time
+const MAX_CONCURRENT_DEVICES: usize = 4;
+
+/// Upper bound for a single D-Bus call while loading device state
+const CALL_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Media player information from the phone
 #[derive(Debug, Clone, Default)]
 pub struct MediaPlayerInfo {
@@ -20,6 +40,91 @@ pub struct MediaPlayerInfo {
     pub can_go_next: bool,
     pub can_go_previous: bool,
     pub can_seek: bool,
+    /// Cached copy of the track's album art
+    pub album_art: Option<PathBuf>,
+    /// When `position` was read
+    pub fetched_at: Option<Instant>,
+}
+
+impl MediaPlayerInfo {
+    /// Playback position in milliseconds, extrapolated from the last read
+    /// while playing since the phone only reports it on changes
+    pub fn current_position(&self) -> i64 {
+        let elapsed = match self.fetched_at {
+            Some(fetched_at) if self.is_playing => fetched_at.elapsed().as_millis() as i64,
+            _ => 0,
+        };
+        let position = self.position + elapsed;
+        if self.length > 0 {
+            position.min(self.length)
+        } else {
+            position
+        }
+    }
+}
+
+/// Why a call to kdeconnectd failed, in terms the UI can show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KdeConnectError {
+    /// kdeconnectd is not on the session bus
+    DaemonNotRunning,
+    /// The device is known but currently out of reach
+    DeviceUnreachable(String),
+    /// The plugin behind this action is disabled or not loaded
+    PluginDisabled(String),
+    /// The daemon does not implement the method or property
+    MethodMissing(String),
+    /// The daemon did not answer in time
+    Timeout,
+    /// The call was refused
+    Permission(String),
+    /// Anything else, with the underlying message
+    Failed(String),
+}
+
+impl std::fmt::Display for KdeConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DaemonNotRunning => write!(f, "KDE Connect is not running"),
+            Self::DeviceUnreachable(_) => write!(f, "Device is not reachable"),
+            Self::PluginDisabled(plugin) => write!(f, "The {} plugin is disabled", plugin),
+            Self::MethodMissing(_) => write!(f, "Not supported by this KDE Connect version"),
+            Self::Timeout => write!(f, "KDE Connect did not respond"),
+            Self::Permission(_) => write!(f, "Permission denied"),
+            Self::Failed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for KdeConnectError {}
+
+impl From<zbus::Error> for KdeConnectError {
+    fn from(error: zbus::Error) -> Self {
+        let name = match &error {
+            zbus::Error::MethodError(name, _, _) => Some(name.to_string()),
+            zbus::Error::FDO(e) => Some(zbus::DBusError::name(e.as_ref()).to_string()),
+            _ => None,
+        };
+        let detail = error.to_string();
+
+        match name.as_deref().and_then(|n| n.strip_prefix("org.freedesktop.DBus.Error.")) {
+            Some("ServiceUnknown" | "NameHasNoOwner") => Self::DaemonNotRunning,
+            Some("UnknownObject" | "UnknownInterface") => Self::PluginDisabled(detail),
+            Some("UnknownMethod" | "UnknownProperty") => Self::MethodMissing(detail),
+            Some("NoReply" | "Timeout" | "TimedOut") => Self::Timeout,
+            Some("AccessDenied" | "AuthFailed" | "InteractiveAuthorizationRequired") => Self::Permission(detail),
+            _ => Self::Failed(detail),
+        }
+    }
+}
+
+/// Whether kdeconnectd currently owns its bus name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaemonStatus {
+    Running,
+    NotRunning,
+    /// The daemon (re)appeared and device state is being reloaded
+    Restarting,
 }
 
 // NEW: Connection pool for reuse and cleanup
@@ -28,11 +133,12 @@ lazy_static::lazy_static! {
 }
 
 // NEW: Get or create shared connection
-async fn get_connection() -> Result<Connection, Box<dyn std::error::Error + Send + Sync>> {
+pub(crate) async fn get_connection() -> zbus::Result<Connection> {
     let mut pool = CONNECTION_POOL.lock().await;
     
     if let Some(conn) = pool.as_ref() {
-        // Just return the existing connection - zbus 5.1 doesn't have is_closed()
+        // zbus can't tell us the connection is closed; the device state service
+        // notices instead and calls cleanup() so the next caller reconnects
         return Ok(conn.clone());
     }
     
@@ -50,1006 +156,658 @@ pub async fn cleanup() {
     }
 }
 
-pub async fn fetch_devices() -> Vec<Device> {
-    let mut devices = Vec::new();
-
-    // CHANGED: Use connection pool instead of creating new connection
-    match get_connection().await {
-        Ok(conn) => {
-            let daemon_path = "/modules/kdeconnect";
-            let daemon_interface = "org.kde.kdeconnect.daemon";
-            
-            let result = conn.call_method(
-                Some("org.kde.kdeconnect"),
-                daemon_path,
-                Some(daemon_interface),
-                "devices",
-                &(false, false)
-            ).await;
-            
-            match result {
-                Ok(reply) => {
-                    let body = reply.body();
-                    match body.deserialize::<Vec<String>>() {
-                        Ok(device_ids) => {
-                            for device_id in device_ids {
-                                let path = format!("/modules/kdeconnect/devices/{}", device_id);
-                                
-                                let name = get_device_property(&conn, &path, "name").await.unwrap_or_else(|_| "Unknown".to_string());
-                                let is_reachable = get_device_property_bool(&conn, &path, "isReachable").await.unwrap_or(false);
-                                let is_paired = get_device_property_bool(&conn, &path, "isPaired").await.unwrap_or(false);
-                                let device_type = get_device_property(&conn, &path, "type").await.unwrap_or_else(|_| "phone".to_string());
-                                
-                                let has_battery = check_plugin(&conn, &path, "kdeconnect_battery").await;
-                                let has_ping = check_plugin(&conn, &path, "kdeconnect_ping").await;
-                                let has_share = check_plugin(&conn, &path, "kdeconnect_share").await;
-                                let has_findmyphone = check_plugin(&conn, &path, "kdeconnect_findmyphone").await;
-                                let has_sms = check_plugin(&conn, &path, "kdeconnect_sms").await;
-                                let has_clipboard = check_plugin(&conn, &path, "kdeconnect_clipboard").await;
-                                let has_contacts = check_plugin(&conn, &path, "kdeconnect_contacts").await;
-                                let has_mpris = check_plugin(&conn, &path, "kdeconnect_mprisremote").await;
-                                let has_remote_keyboard = check_plugin(&conn, &path, "kdeconnect_remotekeyboard").await;
-                                let has_sftp = check_plugin(&conn, &path, "kdeconnect_sftp").await;
-                                let has_presenter = check_plugin(&conn, &path, "kdeconnect_presenter").await;
-                                let has_lockdevice = check_plugin(&conn, &path, "kdeconnect_lockdevice").await;
-                                let has_virtualmonitor = check_plugin(&conn, &path, "kdeconnect_virtualmonitor").await;
-                                
-                                let (battery_level, is_charging) = if has_battery {
-                                    let battery_path = format!("/modules/kdeconnect/devices/{}/battery", device_id);
-                                    let level = get_plugin_property_int(&conn, &battery_path, "org.kde.kdeconnect.device.battery", "charge").await.ok();
-                                    let charging = get_plugin_property_bool(&conn, &battery_path, "org.kde.kdeconnect.device.battery", "isCharging").await.ok();
-                                    (level, charging)
-                                } else {
-                                    (None, None)
-                                };
-
-                                // Fetch connectivity/signal strength information
-                                let has_connectivity = check_plugin(&conn, &path, "kdeconnect_connectivity_report").await;
-                                let (signal_strength, network_type) = if has_connectivity {
-                                    let connectivity_path = format!("/modules/kdeconnect/devices/{}/connectivity_report", device_id);
-                                    let strength = get_plugin_property_int(&conn, &connectivity_path, "org.kde.kdeconnect.device.connectivity_report", "cellularNetworkStrength").await.ok();
-                                    let net_type = get_plugin_property_string(&conn, &connectivity_path, "org.kde.kdeconnect.device.connectivity_report", "cellularNetworkType").await.ok();
-                                    (strength, net_type)
-                                } else {
-                                    (None, None)
-                                };
-
-                                let pairing_requests = get_device_property_int(&conn, &path, "pairingRequestsCount").await.unwrap_or(0);
-
-                                devices.push(Device {
-                                    id: device_id,
-                                    name,
-                                    device_type,
-                                    is_reachable,
-                                    is_paired,
-                                    battery_level,
-                                    is_charging,
-                                    has_battery,
-                                    has_ping,
-                                    has_share,
-                                    has_findmyphone,
-                                    has_sms,
-                                    has_clipboard,
-                                    has_contacts,
-                                    has_mpris,
-                                    has_remote_keyboard,
-                                    has_sftp,
-                                    has_presenter,
-                                    has_lockdevice,
-                                    has_virtualmonitor,
-                                    pairing_requests,
-                                    signal_strength,
-                                    network_type,
-                                    available_players: Vec::new(),
-                                    current_player: None,
-                                    media_info: None,
-                                });
-                            }
-                        }
-                        Err(_) => {}
-                    }
-                }
-                Err(_) => {}
-            }
+/// Start kdeconnectd.
+///
+/// D-Bus activation is tried first, since distributions install a service file
+/// for `org.kde.kdeconnect`; without one, the daemon binary is spawned directly.
+pub async fn start_daemon() -> Result<(), KdeConnectError> {
+    eprintln!("=== Starting KDE Connect daemon ===");
+    
+    let conn = get_connection().await?;
+    let activation = async {
+        let bus = zbus::fdo::DBusProxy::new(&conn).await?;
+        bus.start_service_by_name(proxies::SERVICE.try_into()?, 0).await?;
+        Ok::<_, zbus::Error>(())
+    }.await;
+    
+    match activation {
+        Ok(_) => {
+            eprintln!("Ã¢Å“â€œ Daemon activated over D-Bus");
+            return Ok(());
         }
-        Err(_) => {}
+        Err(e) => eprintln!("D-Bus activation failed: {:?}", e),
     }
+    
+    for binary in ["kdeconnectd", "/usr/lib/kdeconnectd", "/usr/libexec/kdeconnectd"] {
+        if tokio::process::Command::new(binary).spawn().is_ok() {
+            eprintln!("Ã¢Å“â€œ Spawned {}", binary);
+            return Ok(());
+        }
+    }
+    
+    eprintln!("Ã¢Å“â€” Could not start kdeconnectd");
+    Err(KdeConnectError::Failed("Could not start kdeconnectd".to_string()))
+}
 
-    devices
+/// Proxy for a device or one of its plugin objects on the shared connection
+async fn device_object<P: DeviceObject>(device_id: &str) -> zbus::Result<P> {
+    let conn = get_connection().await?;
+    proxies::device_object(&conn, device_id).await
 }
 
-async fn get_device_property(conn: &Connection, path: &str, property: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &("org.kde.kdeconnect.device", property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::Str(s) = value {
-        Ok(s.to_string())
+pub async fn fetch_devices() -> Result<Vec<Device>, KdeConnectError> {
+    // CHANGED: Use connection pool instead of creating new connection
+    let conn = get_connection().await?;
+
+    let device_ids = with_timeout(async {
+        proxies::daemon(&conn).await?.devices(false, false).await
+    }).await?;
+
+    // Query several devices at once so one slow device can't hold up the rest
+    let devices = futures::stream::iter(device_ids)
+        .map(|device_id| fetch_device(&conn, device_id))
+        .buffer_unordered(MAX_CONCURRENT_DEVICES)
+        .filter_map(|device| async move { device.ok() })
+        .collect()
+        .await;
+
+    Ok(devices)
+}
+
+/// Load the full state of a single device.
+///
+/// All queries run concurrently, each under [`CALL_TIMEOUT`]. Anything that
+/// times out is left at its default, so a half-asleep device still shows up.
+pub async fn fetch_device(conn: &Connection, device_id: String) -> Result<Device, KdeConnectError> {
+    let device = with_timeout(proxies::device_object::<DeviceProxy>(conn, &device_id)).await?;
+
+    let (name, is_reachable, is_paired, device_type, pair_state, supported, loaded, enabled_checks) = futures::join!(
+        timed(device.name()),
+        timed(device.is_reachable()),
+        timed(device.is_paired()),
+        timed(device.device_type()),
+        timed(device.pair_state()),
+        timed(device.supported_plugins()),
+        timed(device.loaded_plugins()),
+        futures::future::join_all(Plugin::ALL.iter().map(|plugin| timed(device.is_plugin_enabled(plugin.id())))),
+    );
+
+    // Plugin IDs this app doesn't know about are ignored
+    let known = |ids: Option<Vec<String>>| {
+        ids.unwrap_or_default()
+            .iter()
+            .filter_map(|id| Plugin::from_id(id))
+            .collect()
+    };
+
+    let plugins = PluginSet {
+        supported: known(supported),
+        enabled: Plugin::ALL
+            .into_iter()
+            .zip(enabled_checks)
+            .filter(|(_, enabled)| enabled.unwrap_or(false))
+            .map(|(plugin, _)| plugin)
+            .collect(),
+        loaded: known(loaded),
+    };
+
+    let has_battery = plugins.is_loaded(Plugin::Battery);
+    let has_connectivity = plugins.is_loaded(Plugin::ConnectivityReport);
+
+    let ((battery_level, is_charging), (signal_strength, network_type)) = futures::join!(
+        async {
+            if !has_battery {
+                return (None, None);
+            }
+            match timed(proxies::device_object::<BatteryProxy>(conn, &device_id)).await {
+                Some(battery) => futures::join!(timed(battery.charge()), timed(battery.is_charging())),
+                None => (None, None),
+            }
+        },
+        // Fetch connectivity/signal strength information
+        async {
+            if !has_connectivity {
+                return (None, None);
+            }
+            match timed(proxies::device_object::<ConnectivityReportProxy>(conn, &device_id)).await {
+                Some(report) => futures::join!(
+                    timed(report.cellular_network_strength()),
+                    timed(report.cellular_network_type()),
+                ),
+                None => (None, None),
+            }
+        },
+    );
+
+    let is_paired = is_paired.unwrap_or(false);
+    let pair_state = match pair_state {
+        Some(state) => PairState::from_daemon(state),
+        None if is_paired => PairState::Paired,
+        None => PairState::NotPaired,
+    };
+
+    let verification_key = if pair_state.is_pending() {
+        verification_key(&device).await
     } else {
-        Err("Not a string".into())
-    }
+        None
+    };
+
+    Ok(Device {
+        id: device_id,
+        name: name.unwrap_or_else(|| "Unknown".to_string()),
+        device_type: device_type.unwrap_or_else(|| "phone".to_string()),
+        is_reachable: is_reachable.unwrap_or(false),
+        is_paired,
+        pair_state,
+        battery_level,
+        is_charging,
+        plugins,
+        verification_key,
+        signal_strength,
+        network_type,
+        available_players: Vec::new(),
+        current_player: None,
+        media_info: None,
+    })
 }
 
-async fn get_device_property_bool(conn: &Connection, path: &str, property: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &("org.kde.kdeconnect.device", property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::Bool(b) = value {
-        Ok(b)
-    } else {
-        Err("Not a bool".into())
-    }
+/// Read the pairing verification key of a device, if one is being shown
+pub async fn fetch_verification_key(conn: &Connection, device_id: &str) -> Option<String> {
+    let device = timed(proxies::device_object::<DeviceProxy>(conn, device_id)).await?;
+    verification_key(&device).await
 }
 
-async fn get_device_property_int(conn: &Connection, path: &str, property: &str) -> Result<i32, Box<dyn std::error::Error + Send + Sync>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &("org.kde.kdeconnect.device", property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::I32(i) = value {
-        Ok(i)
-    } else {
-        Err("Not an int".into())
+async fn verification_key(device: &DeviceProxy<'_>) -> Option<String> {
+    timed(device.verification_key()).await.filter(|key| !key.is_empty())
+}
+
+/// Run a D-Bus call under [`CALL_TIMEOUT`], treating failure and timeout alike
+async fn timed<T>(call: impl Future<Output = zbus::Result<T>>) -> Option<T> {
+    with_timeout(call).await.ok()
+}
+
+/// Run a D-Bus call under [`CALL_TIMEOUT`]
+async fn with_timeout<T>(call: impl Future<Output = zbus::Result<T>>) -> Result<T, KdeConnectError> {
+    match tokio::time::timeout(CALL_TIMEOUT, call).await {
+        Ok(result) => Ok(result?),
+        Err(_) => {
+            eprintln!("D-Bus call timed out after {:?}", CALL_TIMEOUT);
+            Err(KdeConnectError::Timeout)
+        }
     }
 }
 
-async fn check_plugin(conn: &Connection, path: &str, plugin: &str) -> bool {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.kde.kdeconnect.device"),
-        "hasPlugin",
-        &(plugin,)
-    ).await;
-    
-    if let Ok(reply) = result {
-        let body = reply.body();
-        body.deserialize::<bool>().unwrap_or(false)
-    } else {
-        false
+/// Call a device or plugin object and classify any failure.
+///
+/// kdeconnectd only exports a plugin's object while the plugin is loaded, and
+/// unloads every plugin when the device goes away, so a missing object is
+/// told apart by asking the device whether it is still reachable.
+async fn call<P, T, F, Fut>(device_id: &str, method: F) -> Result<T, KdeConnectError>
+where
+    P: DeviceObject,
+    F: FnOnce(P) -> Fut,
+    Fut: Future<Output = zbus::Result<T>>,
+{
+    let conn = get_connection().await?;
+
+    let result = with_timeout(async {
+        let proxy = proxies::device_object::<P>(&conn, device_id).await?;
+        method(proxy).await
+    }).await;
+
+    match result {
+        Err(KdeConnectError::PluginDisabled(_)) => {
+            let reachable = timed(async {
+                proxies::device_object::<DeviceProxy>(&conn, device_id).await?.is_reachable().await
+            }).await;
+
+            match reachable {
+                Some(true) if !P::SUB_PATH.is_empty() => Err(KdeConnectError::PluginDisabled(P::SUB_PATH.to_string())),
+                _ => Err(KdeConnectError::DeviceUnreachable(device_id.to_string())),
+            }
+        }
+        other => other,
     }
 }
 
 // Device Actions
-pub async fn ping_device(device_id: String) {
+pub async fn ping_device(device_id: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Sending Ping ===");
     eprintln!("Device: {}", device_id);
     
-    // CHANGED: Use connection pool
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/ping", device_id);
-        
-        eprintln!("Path: {}", path);
-        eprintln!("Interface: org.kde.kdeconnect.device.ping");
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.ping"),
-            "sendPing",
-            &()
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Ping sent successfully"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to send ping: {:?}", e),
-        }
+    let result = call(&device_id, |ping: PingProxy| async move { ping.send_ping().await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Ping sent successfully"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to send ping: {:?}", e),
     }
+    result
 }
 
-pub async fn pair_device(device_id: String) {
+pub async fn pair_device(device_id: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Pairing Device ===");
     eprintln!("Device: {}", device_id);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device"),
-            "requestPairing",
-            &()
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Pairing request sent"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to request pairing: {:?}", e),
-        }
+    let result = call(&device_id, |device: DeviceProxy| async move { device.request_pairing().await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Pairing request sent"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to request pairing: {:?}", e),
     }
+    result
 }
 
-pub async fn unpair_device(device_id: String) {
+pub async fn unpair_device(device_id: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Unpairing Device ===");
     eprintln!("Device: {}", device_id);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device"),
-            "unpair",
-            &()
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Device unpaired"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to unpair: {:?}", e),
-        }
+    let result = call(&device_id, |device: DeviceProxy| async move { device.unpair().await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Device unpaired"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to unpair: {:?}", e),
     }
+    result
 }
 
-pub async fn accept_pairing(device_id: String) {
+pub async fn accept_pairing(device_id: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Accepting Pairing ===");
     eprintln!("Device: {}", device_id);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device"),
-            "acceptPairing",
-            &()
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Pairing accepted"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to accept pairing: {:?}", e),
-        }
+    let result = call(&device_id, |device: DeviceProxy| async move { device.accept_pairing().await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Pairing accepted"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to accept pairing: {:?}", e),
     }
+    result
 }
 
-pub async fn reject_pairing(device_id: String) {
+pub async fn reject_pairing(device_id: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Rejecting Pairing ===");
     eprintln!("Device: {}", device_id);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device"),
-            "rejectPairing",
-            &()
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Pairing rejected"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to reject pairing: {:?}", e),
-        }
+    let result = call(&device_id, |device: DeviceProxy| async move { device.reject_pairing().await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Pairing rejected"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to reject pairing: {:?}", e),
     }
+    result
 }
 
-pub async fn find_my_phone(device_id: String) {
+pub async fn find_my_phone(device_id: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Finding Phone ===");
     eprintln!("Device: {}", device_id);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/findmyphone", device_id);
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.findmyphone"),
-            "ring",
-            &()
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Phone is ringing"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to ring phone: {:?}", e),
-        }
+    let result = call(&device_id, |phone: FindMyPhoneProxy| async move { phone.ring().await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Phone is ringing"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to ring phone: {:?}", e),
     }
+    result
 }
 
 // Alias for compatibility with main.rs
-pub async fn ring_device(device_id: String) {
-    find_my_phone(device_id).await;
+pub async fn ring_device(device_id: String) -> Result<(), KdeConnectError> {
+    find_my_phone(device_id).await
 }
 
-pub async fn share_file(device_id: String, file_path: String) {
+pub async fn share_file(device_id: String, file_path: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Sharing File ===");
     eprintln!("Device: {}", device_id);
     eprintln!("File: {}", file_path);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/share", device_id);
-        
-        // Convert file path to file:// URL
-        let file_url = if file_path.starts_with("file://") {
-            file_path
-        } else {
-            format!("file://{}", file_path)
-        };
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.share"),
-            "shareUrl",
-            &(file_url,)
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ File shared successfully"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to share file: {:?}", e),
-        }
+    let file_url = crate::shares::file_url(&file_path);
+    
+    let result = call(&device_id, |share: ShareProxy| async move { share.share_url(&file_url).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ File shared successfully"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to share file: {:?}", e),
     }
+    result
 }
 
-// Share multiple files
-pub async fn share_files(device_id: String, file_paths: Vec<String>) {
-    for file_path in file_paths {
-        share_file(device_id.clone(), file_path).await;
+/// Send text to the device, which offers to copy or open it
+pub async fn share_text(device_id: String, text: String) -> Result<(), KdeConnectError> {
+    eprintln!("=== Sharing Text ===");
+    eprintln!("Device: {}", device_id);
+    
+    let result = call(&device_id, |share: ShareProxy| async move { share.share_text(&text).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Text shared successfully"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to share text: {:?}", e),
     }
+    result
 }
 
-#[allow(dead_code)]
-// Enhanced browse_files function with comprehensive debugging
-// Replace the existing browse_files function in dbus.rs with this version
-
-#[allow(dead_code)]
-// src/dbus.rs - browse_files function
-// Final version that handles stale SSHFS mounts
-
-#[allow(dead_code)]
-// src/dbus.rs - browse_files function
-// Corrected version that opens file manager directly (not settings)
-
-#[allow(dead_code)]
-pub async fn browse_files(device_id: String) {
-    eprintln!("\n╔════════════════════════════════════════════════════════════════╗");
-    eprintln!("║              BROWSING DEVICE FILES                             ║");
-    eprintln!("╚════════════════════════════════════════════════════════════════╝");
-    eprintln!("Device ID: {}", device_id);
-    
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/sftp", device_id);
-        
-        // === STEP 1: Check if already mounted ===
-        eprintln!("\n[1] Checking mount status...");
-        let is_mounted = match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.sftp"),
-            "isMounted",
-            &()
-        ).await {
-            Ok(reply) => {
-                let mounted = reply.body().deserialize::<bool>().unwrap_or(false);
-                eprintln!("  → isMounted: {}", mounted);
-                mounted
-            }
-            Err(e) => {
-                eprintln!("  ✗ Failed to check mount status: {:?}", e);
-                false
-            }
-        };
-        
-        // === STEP 2: If mounted, check if it's stale ===
-        if is_mounted {
-            eprintln!("\n[2] Device reports as mounted, checking if accessible...");
-            
-            // Get mount point
-            if let Ok(reply) = conn.call_method(
-                Some("org.kde.kdeconnect"),
-                path.as_str(),
-                Some("org.kde.kdeconnect.device.sftp"),
-                "mountPoint",
-                &()
-            ).await {
-                if let Ok(mount_point) = reply.body().deserialize::<String>() {
-                    if !mount_point.is_empty() {
-                        eprintln!("  → Mount point: {}", mount_point);
-                        
-                        // Check if mount is accessible
-                        if std::path::Path::new(&mount_point).exists() {
-                            match std::fs::read_dir(&mount_point) {
-                                Ok(entries) => {
-                                    let count = entries.count();
-                                    eprintln!("  ✓ Mount is accessible ({} entries)", count);
-                                    eprintln!("\n[3] Opening file manager...");
-                                    open_in_file_manager(&mount_point).await;
-                                    eprintln!("\n✓ Done!");
-                                    return;
-                                }
-                                Err(e) => {
-                                    eprintln!("  ✗ Mount exists but not accessible: {:?}", e);
-                                    eprintln!("  → This is a STALE MOUNT, needs to be fixed");
-                                    
-                                    // Unmount the stale mount
-                                    eprintln!("\n[3] Unmounting stale mount...");
-                                    let _ = conn.call_method(
-                                        Some("org.kde.kdeconnect"),
-                                        path.as_str(),
-                                        Some("org.kde.kdeconnect.device.sftp"),
-                                        "unmount",
-                                        &()
-                                    ).await;
-                                    
-                                    // Force unmount with fusermount
-                                    eprintln!("  → Force unmounting with fusermount...");
-                                    let _ = tokio::process::Command::new("fusermount")
-                                        .args(&["-u", &mount_point])
-                                        .output()
-                                        .await;
-                                    
-                                    // Also try fusermount3
-                                    let _ = tokio::process::Command::new("fusermount3")
-                                        .args(&["-u", &mount_point])
-                                        .output()
-                                        .await;
-                                    
-                                    eprintln!("  → Waiting 2 seconds for unmount...");
-                                    tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
-                                    
-                                    // Fall through to remount below
-                                }
-                            }
-                        } else {
-                            eprintln!("  ✗ Mount point doesn't exist: {}", mount_point);
-                            // Fall through to mount
-                        }
-                    }
-                }
-            }
-        }
-        
-        // === STEP 3: Mount the device ===
-        eprintln!("\n[3] Mounting device...");
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.sftp"),
-            "mount",
-            &()
-        ).await {
-            Ok(_) => {
-                eprintln!("  ✓ Mount command sent");
-                eprintln!("  → Waiting 4 seconds for SSHFS to establish connection...");
-                tokio::time::sleep(tokio::time::Duration::from_millis(4000)).await;
-            }
-            Err(e) => {
-                eprintln!("  ✗ Mount command failed: {:?}", e);
-                eprintln!("\n✗ Cannot mount device. Please check:");
-                eprintln!("   - Phone is connected to same network");
-                eprintln!("   - KDE Connect is running on phone");
-                eprintln!("   - SFTP plugin is enabled in phone settings");
-                return;
-            }
-        }
-        
-        // === STEP 4: Verify mount succeeded ===
-        eprintln!("\n[4] Verifying mount...");
-        let is_now_mounted = match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.sftp"),
-            "isMounted",
-            &()
-        ).await {
-            Ok(reply) => reply.body().deserialize::<bool>().unwrap_or(false),
-            Err(_) => false
-        };
-        
-        if !is_now_mounted {
-            eprintln!("  ✗ Mount verification failed");
-            eprintln!("\n✗ Mount did not succeed. This could mean:");
-            eprintln!("   - SSHFS is not installed on your PC");
-            eprintln!("   - Network connectivity issue");
-            eprintln!("   - Phone rejected the connection");
-            return;
-        }
-        
-        eprintln!("  ✓ Mount verified");
-        
-        // === STEP 5: Get mount point and verify accessibility ===
-        eprintln!("\n[5] Getting mount point...");
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.sftp"),
-            "mountPoint",
-            &()
-        ).await {
-            Ok(reply) => {
-                if let Ok(mount_point) = reply.body().deserialize::<String>() {
-                    if mount_point.is_empty() {
-                        eprintln!("  ✗ Mount point is empty");
-                        return;
-                    }
-                    
-                    eprintln!("  → Mount point: {}", mount_point);
-                    
-                    // === STEP 6: Test accessibility with retries ===
-                    eprintln!("\n[6] Testing mount accessibility...");
-                    
-                    let mut accessible = false;
-                    
-                    for attempt in 1..=5 {
-                        eprintln!("  → Attempt {}/5...", attempt);
-                        
-                        // First check if path exists
-                        if !std::path::Path::new(&mount_point).exists() {
-                            eprintln!("    ✗ Mount point doesn't exist yet");
-                            if attempt < 5 {
-                                eprintln!("    → Waiting 1 second...");
-                                tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-                            }
-                            continue;
-                        }
-                        
-                        // Try to read directory
-                        match std::fs::read_dir(&mount_point) {
-                            Ok(entries) => {
-                                let entry_count = entries.count();
-                                eprintln!("    ✓ Accessible ({} entries)", entry_count);
-                                accessible = true;
-                                
-                                // Show first few files
-                                if let Ok(entries) = std::fs::read_dir(&mount_point) {
-                                    eprintln!("    → Contents:");
-                                    for (i, entry) in entries.enumerate().take(5) {
-                                        if let Ok(entry) = entry {
-                                            eprintln!("      {}. {}", i + 1, entry.file_name().to_string_lossy());
-                                        }
-                                    }
-                                    if entry_count > 5 {
-                                        eprintln!("      ... and {} more", entry_count - 5);
-                                    }
-                                }
-                                break;
-                            }
-                            Err(e) => {
-                                eprintln!("    ✗ Not accessible: {} (kind: {:?})", e, e.kind());
-                                
-                                if attempt == 5 {
-                                    // Last attempt - show diagnostics
-                                    eprintln!("\n    [Diagnostics]");
-                                    
-                                    // Check metadata
-                                    if let Ok(metadata) = std::fs::metadata(&mount_point) {
-                                        #[cfg(unix)]
-                                        {
-                                            use std::os::unix::fs::PermissionsExt;
-                                            let mode = metadata.permissions().mode();
-                                            eprintln!("    → Permissions: {:o}", mode);
-                                        }
-                                    }
-                                    
-                                    // Check if mount is in mount table
-                                    if let Ok(output) = tokio::process::Command::new("mount")
-                                        .output()
-                                        .await
-                                    {
-                                        let mount_output = String::from_utf8_lossy(&output.stdout);
-                                        if let Some(line) = mount_output.lines().find(|l| l.contains(&device_id)) {
-                                            eprintln!("    → System mount: {}", line);
-                                        }
-                                    }
-                                } else {
-                                    eprintln!("    → Waiting 1 second before retry...");
-                                    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
-                                }
-                            }
-                        }
-                    }
-                    
-                    if accessible {
-                        eprintln!("\n[7] Opening file manager...");
-                        eprintln!("  → Path: {}", mount_point);
-                        open_in_file_manager(&mount_point).await;
-                        eprintln!("\n✓ Done! File manager should now be open.");
-                    } else {
-                        eprintln!("\n✗ Mount exists but is not accessible after 5 attempts");
-                        eprintln!("\nPossible issues:");
-                        eprintln!("  1. SSHFS connection is broken (stale mount)");
-                        eprintln!("  2. Network connectivity problem");
-                        eprintln!("  3. Phone's SSH server not responding");
-                        eprintln!("\nTry:");
-                        eprintln!("  - Running: ./fix_stale_mount.sh");
-                        eprintln!("  - Restarting KDE Connect on phone");
-                        eprintln!("  - Checking phone is on same network");
-                        eprintln!("  - Running: systemctl --user restart kdeconnect.service");
-                    }
-                } else {
-                    eprintln!("  ✗ Failed to deserialize mount point");
-                }
-            }
-            Err(e) => {
-                eprintln!("  ✗ Failed to get mount point: {:?}", e);
-            }
-        }
-    } else {
-        eprintln!("✗ Failed to get D-Bus connection");
+/// Send a link to the device, which opens it
+pub async fn share_url(device_id: String, url: String) -> Result<(), KdeConnectError> {
+    eprintln!("=== Sharing URL ===");
+    eprintln!("Device: {}", device_id);
+    eprintln!("URL: {}", url);
+    
+    let result = call(&device_id, |share: ShareProxy| async move { share.share_url(&url).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ URL shared successfully"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to share URL: {:?}", e),
     }
+    result
 }
 
-/// Try using qdbus command-line tool to mount and browse
-#[allow(dead_code)]
-async fn try_qdbus_mount(device_id: &str) {
-    eprintln!("=== Trying qdbus mount ===");
-    
-    // Try qdbus (Qt5) first, then qdbus-qt5 variant
-    let mount_result = match tokio::process::Command::new("qdbus")
-        .args(&[
-            "org.kde.kdeconnect",
-            &format!("/modules/kdeconnect/devices/{}/sftp", device_id),
-            "org.kde.kdeconnect.device.sftp.mount"
-        ])
-        .output()
-        .await
-    {
-        Ok(output) => Ok(output),
-        Err(_) => {
-            // Try qdbus-qt5 variant
-            tokio::process::Command::new("qdbus-qt5")
-                .args(&[
-                    "org.kde.kdeconnect",
-                    &format!("/modules/kdeconnect/devices/{}/sftp", device_id),
-                    "org.kde.kdeconnect.device.sftp.mount"
-                ])
-                .output()
-                .await
-        }
-    };
-    
-    match mount_result {
-        Ok(output) if output.status.success() => {
-            eprintln!("Ã¢Å“â€œ qdbus mount succeeded");
-            tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
-            
-            // Get mount point via qdbus
-            let mountpoint_result = match tokio::process::Command::new("qdbus")
-                .args(&[
-                    "org.kde.kdeconnect",
-                    &format!("/modules/kdeconnect/devices/{}/sftp", device_id),
-                    "org.kde.kdeconnect.device.sftp.mountPoint"
-                ])
-                .output()
-                .await
-            {
-                Ok(output) => Ok(output),
-                Err(_) => {
-                    tokio::process::Command::new("qdbus-qt5")
-                        .args(&[
-                            "org.kde.kdeconnect",
-                            &format!("/modules/kdeconnect/devices/{}/sftp", device_id),
-                            "org.kde.kdeconnect.device.sftp.mountPoint"
-                        ])
-                        .output()
-                        .await
-                }
-            };
-            
-            if let Ok(mp_output) = mountpoint_result {
-                if mp_output.status.success() {
-                    let mount_point = String::from_utf8_lossy(&mp_output.stdout).trim().to_string();
-                    eprintln!("Ã¢Å“â€œ Got mount point via qdbus: {}", mount_point);
-                    
-                    if !mount_point.is_empty() && std::path::Path::new(&mount_point).exists() {
-                        open_in_file_manager(&mount_point).await;
-                        return;
-                    }
-                }
-            }
-            
-            eprintln!("Ã¢Å¡Â  Could not get valid mount point from qdbus");
-        }
-        Ok(output) => {
-            eprintln!("Ã¢Å“â€” qdbus mount failed: {}", String::from_utf8_lossy(&output.stderr));
-        }
-        Err(e) => {
-            eprintln!("Ã¢Å“â€” qdbus not available: {:?}", e);
+// Share multiple files, reporting the first failure once all were attempted
+pub async fn share_files(device_id: String, file_paths: Vec<String>) -> Result<(), KdeConnectError> {
+    let mut first_error = None;
+    for file_path in file_paths {
+        if let Err(e) = share_file(device_id.clone(), file_path).await {
+            first_error.get_or_insert(e);
         }
     }
-    
-    // Final fallback: try startBrowsing via qdbus
-    eprintln!("Trying qdbus startBrowsing...");
-    let _ = match tokio::process::Command::new("qdbus")
-        .args(&[
-            "org.kde.kdeconnect",
-            &format!("/modules/kdeconnect/devices/{}/sftp", device_id),
-            "org.kde.kdeconnect.device.sftp.startBrowsing"
-        ])
-        .spawn()
-    {
-        Ok(child) => Ok(child),
-        Err(_) => {
-            tokio::process::Command::new("qdbus-qt5")
-                .args(&[
-                    "org.kde.kdeconnect",
-                    &format!("/modules/kdeconnect/devices/{}/sftp", device_id),
-                    "org.kde.kdeconnect.device.sftp.startBrowsing"
-                ])
-                .spawn()
-        }
-    };
+    first_error.map_or(Ok(()), Err)
 }
 
-/// Try the startBrowsing D-Bus method
-#[allow(dead_code)]
-async fn try_start_browsing(conn: &Connection, path: &str) {
-    eprintln!("=== Trying startBrowsing ===");
-    match conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.kde.kdeconnect.device.sftp"),
-        "startBrowsing",
-        &()
-    ).await {
-        Ok(_) => eprintln!("Ã¢Å“â€œ startBrowsing called successfully"),
-        Err(e) => eprintln!("Ã¢Å“â€” startBrowsing failed: {:?}", e),
+pub async fn send_clipboard(device_id: String, content: String) -> Result<(), KdeConnectError> {
+    eprintln!("=== Sending Clipboard ===");
+    eprintln!("Device: {}", device_id);
+    
+    let result = call(&device_id, |clipboard: ClipboardProxy| async move { clipboard.send_clipboard_content(&content).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Clipboard sent"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to send clipboard: {:?}", e),
     }
+    result
 }
 
-/// Open a path in the file manager with multiple fallbacks
-#[allow(dead_code)]
-async fn open_in_file_manager(path: &str) {
-    eprintln!("Opening file manager at: {}", path);
-    
-    // Try xdg-open first (respects user's default)
-    match std::process::Command::new("xdg-open")
-        .arg(path)
-        .spawn()
-    {
-        Ok(_) => {
-            eprintln!("Ã¢Å“â€œ Opened with xdg-open");
-            return;
-        }
-        Err(e) => {
-            eprintln!("Ã¢Å¡Â  xdg-open failed: {:?}", e);
-        }
-    }
+/// Dismiss a notification on the device
+pub async fn dismiss_notification(device_id: String, id: String) -> Result<(), KdeConnectError> {
+    eprintln!("=== Dismissing Notification ===");
+    eprintln!("Device: {}", device_id);
     
-    // Try COSMIC Files
-    if std::process::Command::new("cosmic-files")
-        .arg(path)
-        .spawn()
-        .is_ok()
-    {
-        eprintln!("Ã¢Å“â€œ Opened with cosmic-files");
-        return;
-    }
+    // Each notification is an object of its own below the plugin's
+    let path_id = device_id.clone();
+    let result = call(&device_id, |plugin: NotificationsProxy| async move {
+        proxies::notification(plugin.inner().connection(), &path_id, &id).await?.dismiss().await
+    }).await;
     
-    // Try Dolphin (KDE's file manager, most likely to handle KDE Connect mounts well)
-    if std::process::Command::new("dolphin")
-        .arg(path)
-        .spawn()
-        .is_ok()
-    {
-        eprintln!("Ã¢Å“â€œ Opened with dolphin");
-        return;
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Notification dismissed"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to dismiss notification: {:?}", e),
     }
+    result
+}
+
+/// Press one of a notification's action buttons, by the notification's key
+/// and the button's label
+pub async fn send_notification_action(device_id: String, key: String, action: String) -> Result<(), KdeConnectError> {
+    eprintln!("=== Sending Notification Action ===");
+    eprintln!("Device: {}", device_id);
+    eprintln!("Action: {}", action);
     
-    // Try Nautilus (GNOME)
-    if std::process::Command::new("nautilus")
-        .arg(path)
-        .spawn()
-        .is_ok()
-    {
-        eprintln!("Ã¢Å“â€œ Opened with nautilus");
-        return;
-    }
+    let result = call(&device_id, |plugin: NotificationsProxy| async move { plugin.send_action(&key, &action).await }).await;
     
-    // Try Thunar (XFCE)
-    if std::process::Command::new("thunar")
-        .arg(path)
-        .spawn()
-        .is_ok()
-    {
-        eprintln!("Ã¢Å“â€œ Opened with thunar");
-        return;
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Notification action sent"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to send notification action: {:?}", e),
     }
-    
-    eprintln!("Ã¢Å“â€” All file manager attempts failed");
-    eprintln!("Mount point is at: {}", path);
-    eprintln!("You can manually open it in your file manager");
+    result
 }
 
-pub async fn send_clipboard(device_id: String, content: String) {
-    eprintln!("=== Sending Clipboard ===");
+pub async fn reply_to_notification(device_id: String, id: String, message: String) -> Result<(), KdeConnectError> {
+    eprintln!("=== Replying to Notification ===");
     eprintln!("Device: {}", device_id);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/clipboard", device_id);
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.clipboard"),
-            "sendClipboard",
-            &(content,)
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Clipboard sent"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to send clipboard: {:?}", e),
-        }
+    let path_id = device_id.clone();
+    let result = call(&device_id, |plugin: NotificationsProxy| async move {
+        proxies::notification(plugin.inner().connection(), &path_id, &id).await?.send_reply(&message).await
+    }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Reply sent"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to send reply: {:?}", e),
     }
+    result
 }
 
-pub async fn lock_device(device_id: String) {
+pub async fn lock_device(device_id: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Locking Device ===");
     eprintln!("Device: {}", device_id);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/lockdevice", device_id);
-        
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.lockdevice"),
-            "lock",
-            &()
-        ).await {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Device locked"),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to lock device: {:?}", e),
-        }
+    // The plugin exposes locking as a writable property, not a method
+    let result = call(&device_id, |lock: LockDeviceProxy| async move { lock.set_is_locked(true).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Device locked"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to lock device: {:?}", e),
     }
+    result
 }
 
-/// Helper to get bool property from plugin
-async fn get_plugin_property_bool(conn: &Connection, path: &str, interface: &str, property: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &(interface, property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::Bool(b) = value {
-        Ok(b)
-    } else {
-        Ok(false)
+/// Start a virtual display on this machine and have the device show it
+pub async fn start_virtual_monitor(device_id: String) -> Result<(), KdeConnectError> {
+    eprintln!("=== Starting Virtual Monitor ===");
+    eprintln!("Device: {}", device_id);
+    
+    // The plugin only answers with a bool, so check the usual reasons first
+    let result = call(&device_id, |monitor: VirtualMonitorProxy| async move {
+        if !monitor.is_virtual_monitor_available().await.unwrap_or(true) {
+            return Ok(Err("krfb-virtualmonitor is not installed"));
+        }
+        if !monitor.has_remote_vnc_client().await.unwrap_or(true) {
+            return Ok(Err("The device has no VNC client to show the display"));
+        }
+        if !monitor.request_virtual_monitor().await? {
+            return Ok(Err("Could not start the virtual display"));
+        }
+        Ok(Ok(()))
+    }).await.and_then(|started| started.map_err(|reason| KdeConnectError::Failed(reason.to_string())));
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Virtual monitor started"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to start virtual monitor: {:?}", e),
     }
+    result
 }
 
-/// Helper to get i64 property from plugin
-async fn get_plugin_property_i64(conn: &Connection, path: &str, interface: &str, property: &str) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &(interface, property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::I64(i) = value {
-        Ok(i)
-    } else {
-        Ok(0)
+/// Stop the device's virtual display
+pub async fn stop_virtual_monitor(device_id: String) -> Result<(), KdeConnectError> {
+    eprintln!("=== Stopping Virtual Monitor ===");
+    eprintln!("Device: {}", device_id);
+    
+    let result = call(&device_id, |monitor: VirtualMonitorProxy| async move { monitor.stop().await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Virtual monitor stopped"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to stop virtual monitor: {:?}", e),
+    }
+    result
+}
+
+/// A pointer action for the device, sent as a mousepad request
+#[allow(dead_code)] // Used by the remote input window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseCommand {
+    Click,
+    DoubleClick,
+    MiddleClick,
+    RightClick,
+    /// Scroll by a distance in pixels; positive `dy` scrolls down
+    Scroll { dx: f64, dy: f64 },
+}
+
+/// Type a key on the device through the remote keyboard plugin.
+///
+/// `special_key` is a KDE Connect special key code, 0 for a plain `key`.
+#[allow(dead_code)] // Used by the remote input window
+pub async fn send_key_press(
+    device_id: String,
+    key: String,
+    special_key: i32,
+    shift: bool,
+    ctrl: bool,
+    alt: bool,
+) -> Result<(), KdeConnectError> {
+    let result = call(&device_id, |keyboard: RemoteKeyboardProxy| async move {
+        keyboard.send_key_press(&key, special_key, shift, ctrl, alt, false).await
+    }).await;
+    
+    if let Err(e) = &result {
+        eprintln!("Ã¢Å“â€” Failed to send key press: {:?}", e);
+    }
+    result
+}
+
+/// Move the device's pointer by a relative amount
+#[allow(dead_code)] // Used by the remote input window
+pub async fn move_remote_cursor(device_id: String, dx: i32, dy: i32) -> Result<(), KdeConnectError> {
+    let result = call(&device_id, |remote: RemoteControlProxy| async move {
+        remote.move_cursor(&(dx, dy)).await
+    }).await;
+    
+    if let Err(e) = &result {
+        eprintln!("Ã¢Å“â€” Failed to move pointer: {:?}", e);
+    }
+    result
+}
+
+/// Click or scroll on the device
+#[allow(dead_code)] // Used by the remote input window
+pub async fn send_mouse_command(device_id: String, command: MouseCommand) -> Result<(), KdeConnectError> {
+    let result = call(&device_id, |remote: RemoteControlProxy| async move {
+        let mut body = HashMap::new();
+        let action = match command {
+            MouseCommand::Click => "singleclick",
+            MouseCommand::DoubleClick => "doubleclick",
+            MouseCommand::MiddleClick => "middleclick",
+            MouseCommand::RightClick => "rightclick",
+            MouseCommand::Scroll { dx, dy } => {
+                body.insert("dx", Value::from(dx));
+                body.insert("dy", Value::from(dy));
+                "scroll"
+            }
+        };
+        body.insert(action, Value::from(true));
+        remote.send_command(body).await
+    }).await;
+    
+    if let Err(e) = &result {
+        eprintln!("Ã¢Å“â€” Failed to send {:?}: {:?}", command, e);
     }
+    result
 }
 
-/// Helper to get int property from plugin
-async fn get_plugin_property_int(conn: &Connection, path: &str, interface: &str, property: &str) -> Result<i32, Box<dyn std::error::Error + Send + Sync>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &(interface, property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::I32(i) = value {
-        Ok(i)
-    } else {
-        Ok(0)
+/// Enable or disable a plugin for a device in the local configuration
+#[allow(dead_code)] // Used by the settings app
+pub async fn set_plugin_enabled(device_id: String, plugin: Plugin, enabled: bool) -> Result<(), KdeConnectError> {
+    eprintln!("=== Setting Plugin State ===");
+    eprintln!("Device: {}, Plugin: {}, Enabled: {}", device_id, plugin.id(), enabled);
+    
+    let result = call(&device_id, |device: DeviceProxy| async move { device.set_plugin_enabled(plugin.id(), enabled).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Plugin state changed"),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to change plugin state: {:?}", e),
     }
+    result
 }
 
-/// Helper to get string property from plugin
-async fn get_plugin_property_string(conn: &Connection, path: &str, interface: &str, property: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &(interface, property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::Str(s) = value {
-        Ok(s.to_string())
-    } else {
-        Err("Not a string".into())
+/// Identities of the MPRIS2 players running on this machine, as kdeconnectd's
+/// `mpriscontrol` plugin would offer them to the phone.
+///
+/// Players re-exported from phones, by kdeconnectd or by the applet, are left out.
+#[allow(dead_code)] // Used by the settings app
+pub async fn list_local_media_players() -> Result<Vec<String>, KdeConnectError> {
+    eprintln!("=== Listing Local Media Players ===");
+    
+    let conn = get_connection().await?;
+    let names = with_timeout(async {
+        zbus::fdo::DBusProxy::new(&conn).await?.list_names().await.map_err(zbus::Error::from)
+    }).await?;
+    
+    let mut players = Vec::new();
+    for name in names {
+        let Some(suffix) = name.as_str().strip_prefix("org.mpris.MediaPlayer2.") else {
+            continue;
+        };
+        if suffix.starts_with("CosmicConnect.") || suffix.starts_with("kdeconnect.") {
+            continue;
+        }
+        
+        let identity = with_timeout(async {
+            MediaPlayer2Proxy::builder(&conn)
+                .destination(name.to_string())?
+                .cache_properties(CacheProperties::No)
+                .build()
+                .await?
+                .identity()
+                .await
+        }).await;
+        
+        // Fall back to the bus name for players that don't answer
+        let identity = identity.ok().filter(|id| !id.is_empty()).unwrap_or_else(|| {
+            suffix.split('.').next().unwrap_or(suffix).to_string()
+        });
+        if !players.contains(&identity) {
+            players.push(identity);
+        }
     }
+    players.sort();
+    
+    eprintln!("Found {} local players: {:?}", players.len(), players);
+    Ok(players)
 }
 
 /// Get list of available media players on the phone
-pub async fn get_media_player_list(device_id: String) -> Vec<String> {
+pub async fn get_media_player_list(device_id: String) -> Result<Vec<String>, KdeConnectError> {
     eprintln!("=== Getting Media Player List ===");
     eprintln!("Device: {}", device_id);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/mprisremote", device_id);
-        
-        let result = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.freedesktop.DBus.Properties"),
-            "Get",
-            &("org.kde.kdeconnect.device.mprisremote", "playerList")
-        ).await;
-        
-        match result {
-            Ok(reply) => {
-                let body = reply.body();
-                if let Ok(value) = body.deserialize::<zbus::zvariant::Value>() {
-                    if let zbus::zvariant::Value::Array(arr) = value {
-                        let players: Vec<String> = arr.iter()
-                            .filter_map(|v| {
-                                if let zbus::zvariant::Value::Str(s) = v {
-                                    Some(s.to_string())
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        eprintln!("Found {} players: {:?}", players.len(), players);
-                        return players;
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Failed to get player list: {:?}", e);
-            }
-        }
-    }
+    let result = call(&device_id, |mpris: MprisRemoteProxy| async move { mpris.player_list().await }).await;
     
-    Vec::new()
+    match &result {
+        Ok(players) => eprintln!("Found {} players: {:?}", players.len(), players),
+        Err(e) => eprintln!("Failed to get player list: {:?}", e),
+    }
+    result
 }
 
 /// Get current media player information from the phone
-pub async fn get_media_player_info(device_id: String) -> Option<MediaPlayerInfo> {
+pub async fn get_media_player_info(device_id: String) -> Result<MediaPlayerInfo, KdeConnectError> {
     eprintln!("=== Getting Media Player Info ===");
     eprintln!("Device: {}", device_id);
     
-    let conn = get_connection().await.ok()?;
-    let path = format!("/modules/kdeconnect/devices/{}/mprisremote", device_id);
-    let interface = "org.kde.kdeconnect.device.mprisremote";
-    
-    let player = get_plugin_property_string(&conn, &path, interface, "player").await.unwrap_or_default();
-    let title = get_plugin_property_string(&conn, &path, interface, "title").await.unwrap_or_default();
-    let artist = get_plugin_property_string(&conn, &path, interface, "artist").await.unwrap_or_default();
-    let album = get_plugin_property_string(&conn, &path, interface, "album").await.unwrap_or_default();
-    let is_playing = get_plugin_property_bool(&conn, &path, interface, "isPlaying").await.unwrap_or(false);
-    let length = get_plugin_property_i64(&conn, &path, interface, "length").await.unwrap_or(0);
-    let position = get_plugin_property_i64(&conn, &path, interface, "position").await.unwrap_or(0);
-    let volume = get_plugin_property_int(&conn, &path, interface, "volume").await.unwrap_or(50);
-    let can_pause = get_plugin_property_bool(&conn, &path, interface, "canPause").await.unwrap_or(true);
-    let can_play = get_plugin_property_bool(&conn, &path, interface, "canPlay").await.unwrap_or(true);
-    let can_go_next = get_plugin_property_bool(&conn, &path, interface, "canGoNext").await.unwrap_or(true);
-    let can_go_previous = get_plugin_property_bool(&conn, &path, interface, "canGoPrevious").await.unwrap_or(true);
-    let can_seek = get_plugin_property_bool(&conn, &path, interface, "canSeek").await.unwrap_or(false);
+    // The first read tells whether the plugin is there at all; the rest fall back to defaults
+    let (mpris, player) = call(&device_id, |mpris: MprisRemoteProxy| async move {
+        let player = mpris.player().await?;
+        Ok((mpris, player))
+    }).await?;
+    
+    let title = mpris.title().await.unwrap_or_default();
+    let artist = mpris.artist().await.unwrap_or_default();
+    let album = mpris.album().await.unwrap_or_default();
+    let is_playing = mpris.is_playing().await.unwrap_or(false);
+    let length = mpris.length().await.map(i64::from).unwrap_or(0);
+    let position = mpris.position().await.map(i64::from).unwrap_or(0);
+    let fetched_at = Some(Instant::now());
+    let volume = mpris.volume().await.unwrap_or(50);
+    let can_pause = mpris.can_pause().await.unwrap_or(true);
+    let can_play = mpris.can_play().await.unwrap_or(true);
+    let can_go_next = mpris.can_go_next().await.unwrap_or(true);
+    let can_go_previous = mpris.can_go_previous().await.unwrap_or(true);
+    let can_seek = mpris.can_seek().await.unwrap_or(false);
+    
+    let album_art = match mpris.album_art_url().await {
+        Ok(url) => album_art::fetch(&device_id, &[&player, &artist, &album, &title], &url).await,
+        Err(_) => None,
+    };
     
     eprintln!("Player: {}, Title: {}, Artist: {}, Playing: {}", player, title, artist, is_playing);
     
-    Some(MediaPlayerInfo {
+    Ok(MediaPlayerInfo {
         player,
         title,
         artist,
@@ -1063,131 +821,128 @@ pub async fn get_media_player_info(device_id: String) -> Option<MediaPlayerInfo>
         can_go_next,
         can_go_previous,
         can_seek,
+        album_art,
+        fetched_at,
     })
 }
 
 /// Set which media player on the phone to control
-pub async fn set_media_player(device_id: String, player: String) {
+pub async fn set_media_player(device_id: String, player: String) -> Result<(), KdeConnectError> {
     eprintln!("=== Setting Media Player ===");
     eprintln!("Device: {}, Player: {}", device_id, player);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/mprisremote", device_id);
-        
-        let result = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.freedesktop.DBus.Properties"),
-            "Set",
-            &("org.kde.kdeconnect.device.mprisremote", "player", zbus::zvariant::Value::new(player.as_str()))
-        ).await;
-        
-        match result {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Player switched to '{}'", player),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to switch player: {:?}", e),
-        }
+    let name = player.as_str();
+    let result = call(&device_id, |mpris: MprisRemoteProxy| async move { mpris.set_player(name).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Player switched to '{}'", player),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to switch player: {:?}", e),
     }
+    result
 }
 
 /// Send a media action to the phone's media player
-async fn send_media_action(device_id: String, action: &str) {
+async fn send_media_action(device_id: String, action: &str) -> Result<(), KdeConnectError> {
     eprintln!("=== Sending Media Action ===");
     eprintln!("Device: {}, Action: {}", device_id, action);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/mprisremote", device_id);
-        
-        let result = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.mprisremote"),
-            "sendAction",
-            &(action,)
-        ).await;
-        
-        match result {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Action '{}' sent successfully", action),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to send action '{}': {:?}", action, e),
-        }
+    let result = call(&device_id, |mpris: MprisRemoteProxy| async move { mpris.send_action(action).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Action '{}' sent successfully", action),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to send action '{}': {:?}", action, e),
     }
+    result
 }
 
 /// Set volume on the phone's media player (0-100)
-pub async fn set_media_volume(device_id: String, volume: i32) {
+pub async fn set_media_volume(device_id: String, volume: i32) -> Result<(), KdeConnectError> {
     eprintln!("=== Setting Media Volume ===");
     eprintln!("Device: {}, Volume: {}", device_id, volume);
     
     let volume = volume.clamp(0, 100);
     
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/mprisremote", device_id);
-        
-        let result = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.freedesktop.DBus.Properties"),
-            "Set",
-            &("org.kde.kdeconnect.device.mprisremote", "volume", zbus::zvariant::Value::new(volume))
-        ).await;
-        
-        match result {
-            Ok(_) => eprintln!("Ã¢Å“â€œ Volume set to {}", volume),
-            Err(e) => eprintln!("Ã¢Å“â€” Failed to set volume: {:?}", e),
-        }
+    let result = call(&device_id, |mpris: MprisRemoteProxy| async move { mpris.set_volume(volume).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Volume set to {}", volume),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to set volume: {:?}", e),
     }
+    result
 }
 
-/// Get current volume from the phone's media player
-pub async fn get_media_volume(device_id: String) -> Option<i32> {
-    if let Ok(conn) = get_connection().await {
-        let path = format!("/modules/kdeconnect/devices/{}/mprisremote", device_id);
-        let interface = "org.kde.kdeconnect.device.mprisremote";
-        
-        get_plugin_property_int(&conn, &path, interface, "volume").await.ok()
-    } else {
-        None
+/// Seek relative to the current position, in milliseconds
+pub async fn seek_media(device_id: String, offset: i32) -> Result<(), KdeConnectError> {
+    eprintln!("=== Seeking Media ===");
+    eprintln!("Device: {}, Offset: {} ms", device_id, offset);
+    
+    let result = call(&device_id, |mpris: MprisRemoteProxy| async move { mpris.seek(offset).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Seeked by {} ms", offset),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to seek: {:?}", e),
+    }
+    result
+}
+
+/// Jump to an absolute position in the current track, in milliseconds
+pub async fn set_media_position(device_id: String, position: i32) -> Result<(), KdeConnectError> {
+    eprintln!("=== Setting Media Position ===");
+    eprintln!("Device: {}, Position: {} ms", device_id, position);
+    
+    let position = position.max(0);
+    
+    let result = call(&device_id, |mpris: MprisRemoteProxy| async move { mpris.set_position(position).await }).await;
+    
+    match &result {
+        Ok(_) => eprintln!("Ã¢Å“â€œ Position set to {} ms", position),
+        Err(e) => eprintln!("Ã¢Å“â€” Failed to set position: {:?}", e),
     }
+    result
+}
+
+/// Get current volume from the phone's media player
+pub async fn get_media_volume(device_id: String) -> Result<i32, KdeConnectError> {
+    call(&device_id, |mpris: MprisRemoteProxy| async move { mpris.volume().await }).await
 }
 
 // Media control convenience functions
-pub async fn play_media(device_id: String) {
-    send_media_action(device_id, "Play").await;
+pub async fn play_media(device_id: String) -> Result<(), KdeConnectError> {
+    send_media_action(device_id, "Play").await
 }
 
-pub async fn pause_media(device_id: String) {
-    send_media_action(device_id, "Pause").await;
+pub async fn pause_media(device_id: String) -> Result<(), KdeConnectError> {
+    send_media_action(device_id, "Pause").await
 }
 
-#[allow(dead_code)]
-pub async fn play_pause_media(device_id: String) {
-    send_media_action(device_id, "PlayPause").await;
+pub async fn play_pause_media(device_id: String) -> Result<(), KdeConnectError> {
+    send_media_action(device_id, "PlayPause").await
 }
 
 // Alias for main.rs compatibility
 #[allow(dead_code)]
-pub async fn media_play_pause(device_id: String) {
-    play_pause_media(device_id).await;
+pub async fn media_play_pause(device_id: String) -> Result<(), KdeConnectError> {
+    play_pause_media(device_id).await
 }
 
-pub async fn next_media(device_id: String) {
-    send_media_action(device_id, "Next").await;
+pub async fn next_media(device_id: String) -> Result<(), KdeConnectError> {
+    send_media_action(device_id, "Next").await
 }
 
 // Alias for main.rs compatibility
-pub async fn media_next(device_id: String) {
-    next_media(device_id).await;
+pub async fn media_next(device_id: String) -> Result<(), KdeConnectError> {
+    next_media(device_id).await
 }
 
-pub async fn previous_media(device_id: String) {
-    send_media_action(device_id, "Previous").await;
+pub async fn previous_media(device_id: String) -> Result<(), KdeConnectError> {
+    send_media_action(device_id, "Previous").await
 }
 
 // Alias for main.rs compatibility
-pub async fn media_previous(device_id: String) {
-    previous_media(device_id).await;
+pub async fn media_previous(device_id: String) -> Result<(), KdeConnectError> {
+    previous_media(device_id).await
 }
 
-#[allow(dead_code)]
-pub async fn stop_media(device_id: String) {
-    send_media_action(device_id, "Stop").await;
+pub async fn stop_media(device_id: String) -> Result<(), KdeConnectError> {
+    send_media_action(device_id, "Stop").await
 }
\ No newline at end of file
@@ -0,0 +1,402 @@
+// src/device_state.rs
+//! Signal-driven device state service.
+//!
+//! Loads every device once, then keeps the list current from kdeconnectd's
+//! own signals instead of polling: `deviceAdded`, `deviceRemoved` and
+//! `deviceVisibilityChanged` on the daemon; `reachableChanged`, `nameChanged`,
+//! `pluginsChanged` and `pairStateChanged` on each device; `refreshed` on the
+//! battery and connectivity plugins; and `propertiesChanged` on `mprisremote`.
+//!
+//! The daemon itself is tracked through `NameOwnerChanged`. When it goes away
+//! the list is emptied; when it comes back every subscription is rebuilt and
+//! the list reloaded.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures::stream::{AbortHandle, Abortable, BoxStream, FuturesUnordered, SelectAll};
+use futures::{Stream, StreamExt};
+use zbus::Connection;
+
+use crate::dbus::{self, DaemonStatus};
+use crate::models::{Device, PairState};
+use crate::proxies::{self, BatteryProxy, ConnectivityReportProxy, DeviceProxy, MprisRemoteProxy};
+
+/// Incremental change to the device list.
+#[derive(Debug, Clone)]
+pub enum DeviceEvent {
+    /// The full list, sent once when the service starts.
+    Snapshot(Vec<Device>),
+    /// A device appeared or one of its fields changed.
+    Updated(Device),
+    /// A device is no longer known to the daemon.
+    Removed(String),
+    /// kdeconnectd stopped, started or was replaced.
+    Daemon(DaemonStatus),
+    /// The device's media player state changed; media info is not part of
+    /// [`Device`], so it has to be fetched separately.
+    MediaChanged(String),
+}
+
+/// Delay before reopening the session bus connection after it was lost.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// A raw signal, tagged with the device it concerns.
+enum Change {
+    Added(String),
+    Removed(String),
+    Reachable(String, bool),
+    Name(String, String),
+    Plugins(String),
+    PairState(String, i32),
+    Battery(String, bool, i32),
+    Connectivity(String, String, i32),
+    Media(String),
+}
+
+type ChangeStream = BoxStream<'static, Change>;
+
+/// Stream of device events for an iced subscription.
+///
+/// Nothing happens until the stream is first polled, so it is safe to build
+/// on every `subscription()` call.
+pub fn watch() -> impl Stream<Item = DeviceEvent> {
+    async_stream::stream! {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        tokio::spawn(supervise(tx));
+
+        while let Some(event) = rx.recv().await {
+            yield event;
+        }
+    }
+}
+
+type EventSender = tokio::sync::mpsc::UnboundedSender<DeviceEvent>;
+
+/// Keep the service alive across daemon restarts and lost bus connections.
+async fn supervise(tx: EventSender) {
+    loop {
+        match dbus::get_connection().await {
+            Ok(conn) => {
+                if let Err(e) = follow_daemon(&conn, &tx).await {
+                    eprintln!("Device state service lost the bus: {:?}", e);
+                }
+            }
+            Err(e) => eprintln!("Device state service can't reach the bus: {:?}", e),
+        }
+
+        if tx.is_closed() {
+            return;
+        }
+
+        // The pooled connection is dead; drop it so the next call opens a new one
+        dbus::cleanup().await;
+        let _ = tx.send(DeviceEvent::Daemon(DaemonStatus::NotRunning));
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Run the device service whenever kdeconnectd owns its name.
+///
+/// Returns `Ok` once the receiving side is gone, and an error when the bus
+/// connection itself fails.
+async fn follow_daemon(conn: &Connection, tx: &EventSender) -> zbus::Result<()> {
+    let bus = zbus::fdo::DBusProxy::new(conn).await?;
+    let mut owner_changes = bus
+        .receive_name_owner_changed_with_args(&[(0, proxies::SERVICE)])
+        .await?;
+    let mut running = bus.name_has_owner(proxies::SERVICE.try_into()?).await?;
+
+    loop {
+        if !running {
+            if tx.send(DeviceEvent::Daemon(DaemonStatus::NotRunning)).is_err()
+                || tx.send(DeviceEvent::Snapshot(Vec::new())).is_err()
+            {
+                return Ok(());
+            }
+            running = next_owner(&mut owner_changes).await?;
+            if running && tx.send(DeviceEvent::Daemon(DaemonStatus::Restarting)).is_err() {
+                return Ok(());
+            }
+            continue;
+        }
+
+        // A new owner means a new daemon: drop the old session and its watchers
+        tokio::select! {
+            result = run(conn, tx) => {
+                if tx.is_closed() {
+                    return Ok(());
+                }
+                if let Err(e) = result {
+                    eprintln!("Device state session failed: {:?}", e);
+                }
+                // Fails, and hands over to supervise(), if the connection is gone
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                running = bus.name_has_owner(proxies::SERVICE.try_into()?).await?;
+            }
+            owner = next_owner(&mut owner_changes) => {
+                running = owner?;
+                if running && tx.send(DeviceEvent::Daemon(DaemonStatus::Restarting)).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Wait for the next ownership change; `true` if the name now has an owner.
+async fn next_owner(changes: &mut zbus::fdo::NameOwnerChangedStream) -> zbus::Result<bool> {
+    while let Some(signal) = changes.next().await {
+        if let Ok(args) = signal.args() {
+            return Ok(args.new_owner().is_some());
+        }
+    }
+    Err(zbus::Error::InputOutput(std::sync::Arc::new(std::io::Error::new(
+        std::io::ErrorKind::BrokenPipe,
+        "bus connection closed",
+    ))))
+}
+
+/// Load every device, then apply signals until the channel closes or the
+/// signal streams end.
+async fn run(conn: &Connection, tx: &EventSender) -> zbus::Result<()> {
+    let daemon = proxies::daemon(conn).await?;
+
+    // Subscribe before listing so that nothing added in between is missed
+    let mut changes: SelectAll<ChangeStream> = SelectAll::new();
+    changes.push(
+        daemon.receive_device_added().await?
+            .filter_map(|signal| async move {
+                signal.args().ok().map(|args| Change::Added(args.id().clone()))
+            })
+            .boxed(),
+    );
+    changes.push(
+        daemon.receive_device_removed().await?
+            .filter_map(|signal| async move {
+                signal.args().ok().map(|args| Change::Removed(args.id().clone()))
+            })
+            .boxed(),
+    );
+    changes.push(
+        daemon.receive_device_visibility_changed().await?
+            .filter_map(|signal| async move {
+                signal.args().ok().map(|args| Change::Reachable(args.id().clone(), *args.is_visible()))
+            })
+            .boxed(),
+    );
+
+    let mut devices: HashMap<String, Device> = HashMap::new();
+    let mut watchers: HashMap<String, AbortHandle> = HashMap::new();
+
+    // Loaded concurrently, like any other full refresh
+    let snapshot = dbus::fetch_devices().await
+        .map_err(|e| zbus::Error::Failure(e.to_string()))?;
+    for device in snapshot {
+        if let Ok((stream, handle)) = watch_device(conn, &device.id).await {
+            changes.push(stream);
+            watchers.insert(device.id.clone(), handle);
+        }
+        devices.insert(device.id.clone(), device);
+    }
+
+    if tx.send(DeviceEvent::Snapshot(devices.values().cloned().collect())).is_err()
+        || tx.send(DeviceEvent::Daemon(DaemonStatus::Running)).is_err()
+    {
+        return Ok(());
+    }
+
+    // Full reloads run alongside the signals, so one slow device can't hold up the others
+    let mut fetches: FuturesUnordered<BoxFuture<'_, (String, Option<Device>)>> = FuturesUnordered::new();
+    let fetch = |id: String| -> BoxFuture<'_, (String, Option<Device>)> {
+        Box::pin(async move {
+            let device = dbus::fetch_device(conn, id.clone()).await.ok();
+            (id, device)
+        })
+    };
+
+    loop {
+        let change = tokio::select! {
+            change = changes.next() => match change {
+                Some(change) => change,
+                None => break,
+            },
+            Some((id, device)) = fetches.next(), if !fetches.is_empty() => {
+                // Dropped if the device was removed while it loaded
+                let event = device
+                    .filter(|_| watchers.contains_key(&id))
+                    .map(|device| {
+                        devices.insert(id, device.clone());
+                        DeviceEvent::Updated(device)
+                    });
+                if let Some(event) = event {
+                    if tx.send(event).is_err() {
+                        break;
+                    }
+                }
+                continue;
+            }
+        };
+
+        let event = match change {
+            Change::Added(id) => {
+                if !watchers.contains_key(&id) {
+                    match watch_device(conn, &id).await {
+                        Ok((stream, handle)) => {
+                            changes.push(stream);
+                            watchers.insert(id.clone(), handle);
+                        }
+                        Err(_) => continue,
+                    }
+                }
+                fetches.push(fetch(id));
+                None
+            }
+            Change::Removed(id) => {
+                if let Some(handle) = watchers.remove(&id) {
+                    handle.abort();
+                }
+                devices.remove(&id).map(|_| DeviceEvent::Removed(id))
+            }
+            Change::Plugins(id) => {
+                // Plugin availability affects most fields, so reload the device
+                fetches.push(fetch(id));
+                None
+            }
+            Change::Reachable(id, reachable) => patch(&mut devices, &id, |d| {
+                d.is_reachable = reachable;
+            }),
+            Change::Name(id, name) => patch(&mut devices, &id, |d| {
+                d.name = name;
+            }),
+            Change::PairState(id, state) => {
+                let pair_state = PairState::from_daemon(state);
+                // The key is only valid for the attempt that just started
+                let verification_key = if pair_state.is_pending() {
+                    dbus::fetch_verification_key(conn, &id).await
+                } else {
+                    None
+                };
+                patch(&mut devices, &id, |d| {
+                    d.pair_state = pair_state;
+                    d.is_paired = pair_state == PairState::Paired;
+                    d.verification_key = verification_key;
+                })
+            }
+            Change::Battery(id, is_charging, charge) => patch(&mut devices, &id, |d| {
+                d.battery_level = Some(charge);
+                d.is_charging = Some(is_charging);
+            }),
+            Change::Connectivity(id, network_type, strength) => patch(&mut devices, &id, |d| {
+                d.signal_strength = Some(strength);
+                d.network_type = Some(network_type);
+            }),
+            Change::Media(id) => devices.contains_key(&id).then_some(DeviceEvent::MediaChanged(id)),
+        };
+
+        if let Some(event) = event {
+            if tx.send(event).is_err() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply a change to a known device and return the updated copy.
+fn patch(
+    devices: &mut HashMap<String, Device>,
+    device_id: &str,
+    apply: impl FnOnce(&mut Device),
+) -> Option<DeviceEvent> {
+    let device = devices.get_mut(device_id)?;
+    apply(device);
+    Some(DeviceEvent::Updated(device.clone()))
+}
+
+/// Merge the signals of one device and its battery, connectivity and media
+/// plugins.
+///
+/// The plugin streams are subscribed even when the plugin is not loaded yet;
+/// they simply stay quiet until it is.
+async fn watch_device(conn: &Connection, device_id: &str) -> zbus::Result<(ChangeStream, AbortHandle)> {
+    let device = proxies::device_object::<DeviceProxy>(conn, device_id).await?;
+    let battery = proxies::device_object::<BatteryProxy>(conn, device_id).await?;
+    let connectivity = proxies::device_object::<ConnectivityReportProxy>(conn, device_id).await?;
+    let mpris = proxies::device_object::<MprisRemoteProxy>(conn, device_id).await?;
+
+    let id = device_id.to_string();
+    let streams: Vec<ChangeStream> = vec![
+        device.receive_reachable_changed().await?
+            .filter_map({
+                let id = id.clone();
+                move |signal| {
+                    let id = id.clone();
+                    async move { signal.args().ok().map(|args| Change::Reachable(id, *args.reachable())) }
+                }
+            })
+            .boxed(),
+        device.receive_name_changed().await?
+            .filter_map({
+                let id = id.clone();
+                move |signal| {
+                    let id = id.clone();
+                    async move { signal.args().ok().map(|args| Change::Name(id, args.name().clone())) }
+                }
+            })
+            .boxed(),
+        device.receive_plugins_changed().await?
+            .map({
+                let id = id.clone();
+                move |_| Change::Plugins(id.clone())
+            })
+            .boxed(),
+        device.receive_pair_state_changed().await?
+            .filter_map({
+                let id = id.clone();
+                move |signal| {
+                    let id = id.clone();
+                    async move { signal.args().ok().map(|args| Change::PairState(id, *args.pair_state())) }
+                }
+            })
+            .boxed(),
+        battery.receive_refreshed().await?
+            .filter_map({
+                let id = id.clone();
+                move |signal| {
+                    let id = id.clone();
+                    async move {
+                        signal.args().ok().map(|args| Change::Battery(id, *args.is_charging(), *args.charge()))
+                    }
+                }
+            })
+            .boxed(),
+        connectivity.receive_refreshed().await?
+            .filter_map({
+                let id = id.clone();
+                move |signal| {
+                    let id = id.clone();
+                    async move {
+                        signal.args().ok().map(|args| {
+                            Change::Connectivity(id, args.network_type().clone(), *args.strength())
+                        })
+                    }
+                }
+            })
+            .boxed(),
+        mpris.receive_properties_changed().await?
+            .map({
+                let id = id.clone();
+                move |_| Change::Media(id.clone())
+            })
+            .boxed(),
+    ];
+
+    let (handle, registration) = AbortHandle::new_pair();
+    let merged = Abortable::new(futures::stream::select_all(streams), registration).boxed();
+
+    Ok((merged, handle))
+}
@@ -4,9 +4,19 @@
 //! This library provides shared modules for the KDE Connect applet,
 //! settings window, and SMS window binaries.
 
+pub mod album_art;
+pub mod clipboard;
+pub mod clipboard_watch;
 pub mod dbus;
+pub mod device_state;
 pub mod plugins;
 pub mod messages;
 pub mod models;
+pub mod phone_notifications;
+pub mod plugin_config;
 pub mod portal;
+pub mod proxies;
+pub mod sftp;
+pub mod shares;
+pub mod transfers;
 pub mod ui;
\ No newline at end of file
@@ -2,9 +2,22 @@
 mod models;
 mod messages;
 mod dbus;
+mod album_art;
+mod device_state;
+#[allow(dead_code)] // Full interface surface; the applet only uses part of it
+mod proxies;
 mod ui;
 mod plugins;
 mod notifications;
+mod mpris;
+mod transfers;
+mod shares;
+mod sftp;
+mod clipboard;
+mod clipboard_watch;
+mod phone_notifications;
+#[allow(dead_code)] // Folder pickers and filters are only used by the settings app
+mod portal;
 
 use cosmic::app::Core;
 use cosmic::iced::{window, Limits, Subscription};
@@ -12,14 +25,18 @@ use cosmic::iced::window::Id as SurfaceId;
 use cosmic::iced::Task as Command;
 use cosmic::{Element, Action};
 use cosmic::widget;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use models::Device;
+// Shared with the settings app, which edits the same kdeconnectd config
+use cosmic_connect_applet::plugin_config;
+use models::{Device, Plugin};
 use messages::Message;
+use dbus::DaemonStatus;
 
 const ICON_PHONE: &str = "phone-symbolic";
+const TOAST_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(5);
 
 // NEW: Static receiver for pairing notifications - created once at startup
 lazy_static::lazy_static! {
@@ -33,6 +50,24 @@ pub struct KdeConnectApplet {
     popup: Option<window::Id>,
     expanded_device: Option<String>,
     expanded_player_menu: Option<String>,
+    toasts: HashMap<String, String>, // device_id -> last error
+    daemon_status: DaemonStatus,
+    mpris: mpris::Bridge,
+    seek_drag: Option<(String, i64)>, // device_id, position (ms) while the seek bar is held
+    virtual_displays: HashSet<String>, // devices showing a virtual display started from here
+    mounts: HashMap<String, sftp::MountState>, // device_id -> SFTP mount, when not unmounted
+    active_transfers: HashMap<u32, (String, transfers::Transfer)>, // transfer id -> device_id, transfer
+    transfer_history: Vec<transfers::HistoryEntry>, // oldest first
+    drop_target: Option<String>, // device_id whose card is under a drag
+    share_text: Option<(String, String)>, // device_id, draft of the open "Send text / link" dialog
+    recent_shares: Vec<String>, // newest first
+    clipboard_history: Vec<clipboard::ClipboardItem>, // newest first
+    expanded_clipboard: Option<String>, // device_id showing the clipboard history
+    expanded_transfers: Option<String>, // device_id showing its whole transfer history
+    last_synced_clipboard: Option<String>, // text last sent to devices automatically
+    phone_notifications: HashMap<String, Vec<phone_notifications::PhoneNotification>>, // device_id -> notifications, newest first
+    notification_reply: Option<(String, String, String)>, // device_id, notification id, draft of the open reply
+    mirrored_notifications: HashSet<String>, // devices whose notifications are shown as desktop notifications
 }
 
 impl cosmic::Application for KdeConnectApplet {
@@ -75,11 +110,27 @@ impl cosmic::Application for KdeConnectApplet {
             popup: None,
             expanded_device: None,
             expanded_player_menu: None,
+            toasts: HashMap::new(),
+            daemon_status: DaemonStatus::Running,
+            mpris: mpris::Bridge::start(),
+            seek_drag: None,
+            virtual_displays: HashSet::new(),
+            mounts: HashMap::new(),
+            active_transfers: HashMap::new(),
+            transfer_history: transfers::load_history(),
+            drop_target: None,
+            share_text: None,
+            recent_shares: shares::load_recent(),
+            clipboard_history: Vec::new(),
+            expanded_clipboard: None,
+            expanded_transfers: None,
+            last_synced_clipboard: None,
+            phone_notifications: HashMap::new(),
+            notification_reply: None,
+            mirrored_notifications: phone_notifications::load_mirrored(),
         };
 
-        (applet, Command::perform(dbus::fetch_devices(), |devices| {
-            Action::App(Message::DevicesUpdated(devices))
-        }))
+        (applet, Command::perform(dbus::fetch_devices(), devices_fetched))
     }
 
     fn on_close_requested(&self, _id: SurfaceId) -> Option<Message> {
@@ -94,6 +145,8 @@ impl cosmic::Application for KdeConnectApplet {
             Message::TogglePopup => {
                 if let Some(popup_id) = self.popup.take() {
                     self.expanded_device = None;
+                    self.expanded_clipboard = None;
+                    self.expanded_transfers = None;
                     return cosmic::iced::platform_specific::shell::commands::popup::destroy_popup(popup_id);
                 }
                 
@@ -115,15 +168,17 @@ impl cosmic::Application for KdeConnectApplet {
                     .max_height(700.0);
                 
                 let mpris_devices: Vec<String> = self.devices.values()
-                    .filter(|d| d.has_mpris && d.is_reachable && d.is_paired)
+                    .filter(|d| d.plugins.is_loaded(Plugin::MprisRemote) && d.is_reachable && d.is_paired)
                     .map(|d| d.id.clone())
                     .collect();
                 
                 let mut commands = vec![
                     cosmic::iced::platform_specific::shell::commands::popup::get_popup(popup_settings),
-                    Command::perform(dbus::fetch_devices(), |devices| {
-                        Action::App(Message::DevicesUpdated(devices))
-                    })
+                    // Pick up whatever was copied since the popup was last open
+                    clipboard::read().map(|content| match content {
+                        Some(clipboard::ClipboardContent::Text(item)) => Action::App(Message::ClipboardRead(Some(item))),
+                        _ => Action::App(Message::ClipboardRead(None)),
+                    }),
                 ];
                 
                 for device_id in mpris_devices {
@@ -132,14 +187,14 @@ impl cosmic::Application for KdeConnectApplet {
                     
                     commands.push(Command::perform(
                         async move {
-                            (id_for_players.clone(), dbus::get_media_player_list(id_for_players).await)
+                            (id_for_players.clone(), dbus::get_media_player_list(id_for_players).await.unwrap_or_default())
                         },
                         |(device_id, players)| Action::App(Message::MediaPlayersUpdated(device_id, players))
                     ));
                     
                     commands.push(Command::perform(
                         async move {
-                            (id_for_info.clone(), dbus::get_media_player_info(id_for_info).await)
+                            (id_for_info.clone(), dbus::get_media_player_info(id_for_info).await.ok())
                         },
                         |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
                     ));
@@ -148,21 +203,69 @@ impl cosmic::Application for KdeConnectApplet {
                 return Command::batch(commands);
             }
             Message::RefreshDevices => {
-                return Command::perform(dbus::fetch_devices(), |devices| {
-                    Action::App(Message::DevicesUpdated(devices))
-                });
+                return Command::perform(dbus::fetch_devices(), devices_fetched);
             }
             Message::DevicesUpdated(devices) => {
                 let old_devices = std::mem::take(&mut self.devices);
                 
                 for mut device in devices {
                     if let Some(old_device) = old_devices.get(&device.id) {
-                        device.available_players = old_device.available_players.clone();
-                        device.current_player = old_device.current_player.clone();
-                        device.media_info = old_device.media_info.clone();
+                        keep_media_state(&mut device, old_device);
                     }
                     self.devices.insert(device.id.clone(), device);
                 }
+                self.mpris.sync(self.devices.values());
+                // A display session doesn't survive the connection
+                let devices = &self.devices;
+                self.virtual_displays.retain(|id| devices.get(id).is_some_and(|d| d.is_reachable));
+                self.phone_notifications.retain(|id, _| devices.get(id).is_some_and(|d| d.is_reachable));
+                let disconnected: Vec<_> = self.mounts.keys()
+                    .filter(|id| !devices.get(*id).is_some_and(|d| d.is_reachable))
+                    .cloned()
+                    .collect();
+                for device_id in disconnected {
+                    self.forget_mount(&device_id);
+                }
+                let device_ids: Vec<_> = self.devices.keys().cloned().collect();
+                return self.find_mounts(device_ids);
+            }
+            Message::DeviceUpdated(mut device) => {
+                let old_device = self.devices.get(&device.id);
+                let connected = device.is_reachable && !old_device.is_some_and(|d| d.is_reachable);
+                if let Some(old_device) = old_device {
+                    keep_media_state(&mut device, old_device);
+                }
+                self.mpris.device_updated(&device);
+                if !device.is_reachable {
+                    self.virtual_displays.remove(&device.id);
+                    self.phone_notifications.remove(&device.id);
+                    self.forget_mount(&device.id);
+                }
+                let device_id = device.id.clone();
+                self.devices.insert(device.id.clone(), device);
+                if connected {
+                    return self.find_mounts(vec![device_id]);
+                }
+            }
+            Message::DeviceRemoved(ref device_id) => {
+                self.devices.remove(device_id);
+                self.mpris.device_removed(device_id);
+                self.virtual_displays.remove(device_id);
+                self.phone_notifications.remove(device_id);
+                self.forget_mount(device_id);
+                if self.expanded_device.as_ref() == Some(device_id) {
+                    self.expanded_device = None;
+                }
+            }
+            Message::DaemonStatusChanged(status) => {
+                self.daemon_status = status;
+            }
+            Message::StartDaemon => {
+                self.daemon_status = DaemonStatus::Restarting;
+                return Command::perform(dbus::start_daemon(), |result| match result {
+                    Ok(()) => Action::App(Message::RefreshDevices),
+                    Err(_) => Action::App(Message::DaemonStatusChanged(DaemonStatus::NotRunning)),
+                });
             }
             Message::ToggleDeviceMenu(ref device_id) => {
                 let should_load_media = if self.expanded_device.as_ref() == Some(device_id) {
@@ -170,7 +273,7 @@ impl cosmic::Application for KdeConnectApplet {
                     false
                 } else {
                     self.expanded_device = Some(device_id.clone());
-                    self.devices.get(device_id).map(|d| d.has_mpris).unwrap_or(false)
+                    self.devices.get(device_id).map(|d| d.plugins.is_loaded(Plugin::MprisRemote)).unwrap_or(false)
                 };
                 
                 if should_load_media {
@@ -193,110 +296,299 @@ impl cosmic::Application for KdeConnectApplet {
             Message::PingDevice(ref device_id) => {
                 let id = device_id.clone();
                 return Command::perform(
-                    async move {
-                        dbus::ping_device(id).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    dbus::ping_device(id.clone()),
+                    move |result| action_done(id, result)
                 );
             }
             Message::RingDevice(ref device_id) => {
                 let id = device_id.clone();
                 return Command::perform(
-                    async move {
-                        dbus::ring_device(id).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    dbus::ring_device(id.clone()),
+                    move |result| action_done(id, result)
                 );
             }
             Message::LockDevice(ref device_id) => {
                 let id = device_id.clone();
                 return Command::perform(
-                    async move {
-                        dbus::lock_device(id).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    dbus::lock_device(id.clone()),
+                    move |result| action_done(id, result)
                 );
             }
-            #[allow(unused_variables)]
-            Message::BrowseDevice(ref device_id) => {
+            Message::BrowseDevice(device_id) => {
+                match self.mounts.get(&device_id) {
+                    Some(sftp::MountState::Mounting) => {}
+                    Some(sftp::MountState::Mounted(path)) => {
+                        return Command::perform(
+                            portal::open_path(path.clone(), false),
+                            move |result| opened(device_id, result)
+                        );
+                    }
+                    _ => {
+                        self.mounts.insert(device_id.clone(), sftp::MountState::Mounting);
+                        let id = device_id.clone();
+                        return Command::perform(
+                            sftp::mount(id),
+                            move |state| Action::App(Message::MountStateChanged(device_id, state))
+                        );
+                    }
+                }
+            }
+            Message::UnmountDevice(device_id) => {
                 let id = device_id.clone();
                 return Command::perform(
-                    async move {
-                        dbus::browse_files(id).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    sftp::unmount(id),
+                    move |result| match result {
+                        Ok(()) => Action::App(Message::MountStateChanged(device_id, sftp::MountState::Unmounted)),
+                        Err(e) => Action::App(Message::ActionFailed(device_id, e.to_string())),
+                    }
+                );
+            }
+            Message::MountStateChanged(device_id, state) => {
+                let was_mounting = self.mounts.get(&device_id) == Some(&sftp::MountState::Mounting);
+                match state {
+                    sftp::MountState::Unmounted => {
+                        self.mounts.remove(&device_id);
+                    }
+                    state => {
+                        // Open the files once a mount asked for from the card is ready
+                        let open = match (was_mounting, &state) {
+                            (true, sftp::MountState::Mounted(path)) => Some(path.clone()),
+                            _ => None,
+                        };
+                        self.mounts.insert(device_id.clone(), state);
+                        if let Some(path) = open {
+                            return Command::perform(
+                                portal::open_path(path, false),
+                                move |result| opened(device_id, result)
+                            );
+                        }
+                    }
+                }
+            }
+            Message::TransferChanged(transfer) => {
+                // Jobs don't name the device directly; match it once the title arrives
+                let device_id = self.active_transfers.get(&transfer.id)
+                    .map(|(device_id, _)| device_id.clone())
+                    .or_else(|| self.transfer_device(&transfer));
+                if let Some(device_id) = device_id {
+                    self.active_transfers.insert(transfer.id, (device_id, transfer));
+                }
+            }
+            Message::TransferEnded(transfer) => {
+                let device_id = self.active_transfers.remove(&transfer.id)
+                    .map(|(device_id, _)| device_id)
+                    .or_else(|| self.transfer_device(&transfer));
+                let Some(device_id) = device_id else {
+                    return Command::none();
+                };
+                let device_name = self.devices.get(&device_id)
+                    .map(|d| d.name.clone())
+                    .unwrap_or_default();
+                
+                if transfer.state != transfers::TransferState::Cancelled {
+                    self.transfer_history.push(transfers::HistoryEntry::new(&transfer, &device_id, &device_name));
+                    if let Err(e) = transfers::save_history(&self.transfer_history) {
+                        eprintln!("Failed to save transfer history: {}", e);
+                    }
+                }
+                if let transfers::TransferState::Failed(ref reason) = transfer.state {
+                    let error = format!("{} failed: {}", transfer.file_name(), reason);
+                    return self.update(Message::ActionFailed(device_id, error));
+                }
+                
+                // kdeconnectd renames a received file rather than overwrite one; ask where it should go
+                let received = transfer.path()
+                    .filter(|_| transfer.direction() == transfers::Direction::Incoming)
+                    .filter(|_| transfer.state == transfers::TransferState::Finished);
+                if let Some(path) = received {
+                    if let Some(wanted_name) = transfers::collision(&path) {
+                        return Command::perform(
+                            save_received_as(path.clone(), wanted_name),
+                            move |result| match result {
+                                Ok(new_path) => Action::App(Message::ReceivedFileMoved(path, new_path)),
+                                Err(e) => Action::App(Message::ActionFailed(device_id, e)),
+                            }
+                        );
+                    }
+                }
+            }
+            Message::CancelTransfer(id) => {
+                if let Some((device_id, _)) = self.active_transfers.get(&id) {
+                    let device_id = device_id.clone();
+                    return Command::perform(
+                        transfers::cancel(id),
+                        move |result| action_done(device_id, result)
+                    );
+                }
+            }
+            Message::OpenTransferFile(device_id, path) => {
+                return Command::perform(
+                    portal::open_path(path, false),
+                    move |result| opened(device_id, result)
                 );
             }
+            Message::OpenTransferWith(device_id, path) => {
+                return Command::perform(
+                    portal::open_path(path, true),
+                    move |result| opened(device_id, result)
+                );
+            }
+            Message::OpenTransferFolder(device_id, path) => {
+                return Command::perform(
+                    portal::reveal(path),
+                    move |result| opened(device_id, result)
+                );
+            }
+            Message::ToggleTransferHistory(device_id) => {
+                if self.expanded_transfers.as_ref() == Some(&device_id) {
+                    self.expanded_transfers = None;
+                } else {
+                    self.expanded_transfers = Some(device_id);
+                }
+            }
+            Message::ReceivedFileMoved(old_path, new_path) => {
+                if let Some(entry) = self.transfer_history.iter_mut().rev().find(|entry| entry.path.as_ref() == Some(&old_path)) {
+                    entry.file_name = new_path.file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    entry.path = Some(new_path);
+                    if let Err(e) = transfers::save_history(&self.transfer_history) {
+                        eprintln!("Failed to save transfer history: {}", e);
+                    }
+                }
+            }
             Message::SendFile(ref device_id) => {
                 let id = device_id.clone();
+                let id_for_result = device_id.clone();
                 return Command::perform(
                     async move {
                         // Use xdg-desktop-portal for native COSMIC integration
-                        let files = cosmic_connect_applet::portal::pick_files(
+                        let files = portal::pick_files(
                             "Select files to send",
                             true,  // Allow multiple selection
                             None,  // No file filters
                         ).await;
                         
-                        if !files.is_empty() {
-                            dbus::share_files(id, files).await;
+                        if files.is_empty() {
+                            return Ok(());
                         }
+                        dbus::share_files(id, files).await
                     },
-                    |_| Action::App(Message::RefreshDevices)
+                    move |result| action_done(id_for_result, result)
                 );
             }
-            Message::ShareClipboard(ref device_id) => {
+            Message::ShareClipboard(device_id) => {
+                return clipboard::read().map(move |content| {
+                    let device_id = device_id.clone();
+                    match content {
+                        Some(clipboard::ClipboardContent::Text(item)) => Action::App(Message::SendClipboardItem(device_id, item)),
+                        Some(clipboard::ClipboardContent::Image(image)) => Action::App(Message::SendClipboardImage(device_id, image)),
+                        None => Action::App(Message::ActionFailed(device_id, "Clipboard is empty".to_string())),
+                    }
+                });
+            }
+            Message::SendClipboardImage(device_id, image) => {
                 let id = device_id.clone();
                 return Command::perform(
                     async move {
-                        match std::process::Command::new("wl-paste")
-                            .output()
-                        {
-                            Ok(output) if output.status.success() => {
-                                if let Ok(content) = String::from_utf8(output.stdout) {
-                                    dbus::send_clipboard(id, content).await;
-                                }
-                            }
-                            _ => eprintln!("Failed to get clipboard content"),
-                        }
+                        // The phone receives the picture as a file
+                        let path = tokio::task::spawn_blocking(move || clipboard::save_image(&image))
+                            .await
+                            .map_err(|e| dbus::KdeConnectError::Failed(e.to_string()))?
+                            .map_err(|e| dbus::KdeConnectError::Failed(format!("Could not save the clipboard image: {}", e)))?;
+                        dbus::share_files(id, vec![path.to_string_lossy().into_owned()]).await
                     },
-                    |_| Action::App(Message::RefreshDevices)
+                    move |result| action_done(device_id, result)
                 );
             }
+            Message::SendClipboardItem(device_id, item) => {
+                let send_password = plugin_config::ClipboardPluginConfig::load(&device_id)
+                    .unwrap_or_default()
+                    .send_password;
+                if item.is_password && !send_password {
+                    return self.update(Message::ActionFailed(device_id, "The clipboard holds a password, and sending passwords is turned off".to_string()));
+                }
+                
+                let text = item.text.clone();
+                clipboard::remember(&mut self.clipboard_history, item);
+                let id = device_id.clone();
+                return Command::perform(
+                    dbus::send_clipboard(id, text),
+                    move |result| action_done(device_id, result)
+                );
+            }
+            Message::ClipboardChanged(item) => {
+                // The same text copied again, or copied back from the history, isn't sent twice
+                if self.last_synced_clipboard.as_ref() == Some(&item.text) {
+                    clipboard::remember(&mut self.clipboard_history, item);
+                    return Command::none();
+                }
+                self.last_synced_clipboard = Some(item.text.clone());
+                
+                let devices: Vec<String> = self.devices.values()
+                    .filter(|d| d.is_paired && d.is_reachable && d.plugins.is_loaded(Plugin::Clipboard))
+                    .map(|d| d.id.clone())
+                    .collect();
+                let text = item.text.clone();
+                let is_password = item.is_password;
+                clipboard::remember(&mut self.clipboard_history, item);
+                return Command::perform(
+                    auto_share_targets(devices, is_password),
+                    move |targets| Action::App(Message::AutoShareClipboard(targets, text))
+                );
+            }
+            Message::AutoShareClipboard(targets, text) => {
+                return Command::batch(targets.into_iter().map(|device_id| {
+                    let id = device_id.clone();
+                    Command::perform(
+                        dbus::send_clipboard(id, text.clone()),
+                        move |result| action_done(device_id, result)
+                    )
+                }));
+            }
+            Message::ClipboardRead(item) => {
+                if let Some(item) = item {
+                    clipboard::remember(&mut self.clipboard_history, item);
+                }
+            }
+            Message::CopyClipboardItem(item) => {
+                let text = item.text.clone();
+                clipboard::remember(&mut self.clipboard_history, item);
+                return clipboard::write(text);
+            }
+            Message::ToggleClipboardHistory(device_id) => {
+                if self.expanded_clipboard.as_ref() == Some(&device_id) {
+                    self.expanded_clipboard = None;
+                } else {
+                    self.expanded_clipboard = Some(device_id);
+                }
+            }
             Message::PairDevice(ref device_id) => {
                 let id = device_id.clone();
                 return Command::perform(
-                    async move {
-                        dbus::pair_device(id).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    dbus::pair_device(id.clone()),
+                    move |result| action_done(id, result)
                 );
             }
             Message::UnpairDevice(ref device_id) => {
                 let id = device_id.clone();
                 return Command::perform(
-                    async move {
-                        dbus::unpair_device(id).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    dbus::unpair_device(id.clone()),
+                    move |result| action_done(id, result)
                 );
             }
             Message::AcceptPairing(ref device_id) => {
                 let id = device_id.clone();
                 return Command::perform(
-                    async move {
-                        dbus::accept_pairing(id).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    dbus::accept_pairing(id.clone()),
+                    move |result| action_done(id, result)
                 );
             }
             Message::RejectPairing(ref device_id) => {
                 let id = device_id.clone();
                 return Command::perform(
-                    async move {
-                        dbus::reject_pairing(id).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    dbus::reject_pairing(id.clone()),
+                    move |result| action_done(id, result)
                 );
             }
             Message::SendSMS(ref device_id) => {
@@ -319,15 +611,13 @@ impl cosmic::Application for KdeConnectApplet {
                 let id_for_refresh = device_id.clone();
                 return Command::batch(vec![
                     Command::perform(
-                        async move {
-                            dbus::play_media(id).await;
-                        },
-                        |_| Action::App(Message::RefreshDevices)
+                        dbus::play_media(id.clone()),
+                        move |result| action_done(id, result)
                     ),
                     Command::perform(
                         async move {
                             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await;
+                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await.ok();
                             (id_for_refresh, info)
                         },
                         |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
@@ -339,15 +629,13 @@ impl cosmic::Application for KdeConnectApplet {
                 let id_for_refresh = device_id.clone();
                 return Command::batch(vec![
                     Command::perform(
-                        async move {
-                            dbus::pause_media(id).await;
-                        },
-                        |_| Action::App(Message::RefreshDevices)
+                        dbus::pause_media(id.clone()),
+                        move |result| action_done(id, result)
                     ),
                     Command::perform(
                         async move {
                             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await;
+                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await.ok();
                             (id_for_refresh, info)
                         },
                         |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
@@ -359,15 +647,13 @@ impl cosmic::Application for KdeConnectApplet {
                 let id_for_refresh = device_id.clone();
                 return Command::batch(vec![
                     Command::perform(
-                        async move {
-                            dbus::media_next(id).await;
-                        },
-                        |_| Action::App(Message::RefreshDevices)
+                        dbus::media_next(id.clone()),
+                        move |result| action_done(id, result)
                     ),
                     Command::perform(
                         async move {
                             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await;
+                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await.ok();
                             (id_for_refresh, info)
                         },
                         |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
@@ -379,15 +665,13 @@ impl cosmic::Application for KdeConnectApplet {
                 let id_for_refresh = device_id.clone();
                 return Command::batch(vec![
                     Command::perform(
-                        async move {
-                            dbus::media_previous(id).await;
-                        },
-                        |_| Action::App(Message::RefreshDevices)
+                        dbus::media_previous(id.clone()),
+                        move |result| action_done(id, result)
                     ),
                     Command::perform(
                         async move {
                             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await;
+                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await.ok();
                             (id_for_refresh, info)
                         },
                         |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
@@ -396,26 +680,34 @@ impl cosmic::Application for KdeConnectApplet {
             }
             Message::VolumeUp(ref device_id) => {
                 let id = device_id.clone();
+                let id_for_result = device_id.clone();
                 return Command::perform(
                     async move {
-                        if let Some(current_vol) = dbus::get_media_volume(id.clone()).await {
-                            let new_vol = (current_vol + 10).min(100);
-                            dbus::set_media_volume(id.clone(), new_vol).await;
+                        match dbus::get_media_volume(id.clone()).await {
+                            Ok(current_vol) => {
+                                let new_vol = (current_vol + 10).min(100);
+                                dbus::set_media_volume(id.clone(), new_vol).await
+                            }
+                            Err(e) => Err(e),
                         }
                     },
-                    |_| Action::App(Message::RefreshDevices)
+                    move |result| action_done(id_for_result, result)
                 );
             }
             Message::VolumeDown(ref device_id) => {
                 let id = device_id.clone();
+                let id_for_result = device_id.clone();
                 return Command::perform(
                     async move {
-                        if let Some(current_vol) = dbus::get_media_volume(id.clone()).await {
-                            let new_vol = (current_vol - 10).max(0);
-                            dbus::set_media_volume(id.clone(), new_vol).await;
+                        match dbus::get_media_volume(id.clone()).await {
+                            Ok(current_vol) => {
+                                let new_vol = (current_vol - 10).max(0);
+                                dbus::set_media_volume(id.clone(), new_vol).await
+                            }
+                            Err(e) => Err(e),
                         }
                     },
-                    |_| Action::App(Message::RefreshDevices)
+                    move |result| action_done(id_for_result, result)
                 );
             }
             Message::VolumeChanged(ref device_id, volume) => {
@@ -423,46 +715,157 @@ impl cosmic::Application for KdeConnectApplet {
                 let id_for_refresh = device_id.clone();
                 return Command::batch(vec![
                     Command::perform(
-                        async move {
-                            dbus::set_media_volume(id, volume).await;
-                        },
-                        |_| Action::App(Message::RefreshDevices)
+                        dbus::set_media_volume(id.clone(), volume),
+                        move |result| action_done(id, result)
                     ),
                     Command::perform(
                         async move {
                             tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
-                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await;
+                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await.ok();
                             (id_for_refresh, info)
                         },
                         |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
                     )
                 ]);
             }
-            Message::ShareUrl(ref device_id, ref url) => {
+            Message::ShareUrl(device_id, url) => {
+                self.remember_share(&url);
                 let id = device_id.clone();
-                let url_clone = url.clone();
                 return Command::perform(
-                    async move {
-                        dbus::share_files(id, vec![url_clone]).await;
-                    },
-                    |_| Action::App(Message::RefreshDevices)
+                    dbus::share_url(id, url),
+                    move |result| action_done(device_id, result)
+                );
+            }
+            Message::ShareText(device_id, text) => {
+                self.remember_share(&text);
+                let id = device_id.clone();
+                return Command::perform(
+                    dbus::share_text(id, text),
+                    move |result| action_done(device_id, result)
                 );
             }
+            Message::NotificationPosted(device_id, notification) => {
+                let notifications = self.phone_notifications.entry(device_id).or_default();
+                match notifications.iter_mut().find(|n| n.id == notification.id) {
+                    Some(existing) => *existing = notification,
+                    None => notifications.insert(0, notification),
+                }
+            }
+            Message::NotificationRemoved(device_id, id) => {
+                if let Some(notifications) = self.phone_notifications.get_mut(&device_id) {
+                    notifications.retain(|n| n.id != id);
+                }
+                if self.notification_reply.as_ref().is_some_and(|(device, notification, _)| device == &device_id && notification == &id) {
+                    self.notification_reply = None;
+                }
+            }
+            Message::NotificationsCleared(device_id) => {
+                self.phone_notifications.remove(&device_id);
+                if self.notification_reply.as_ref().is_some_and(|(device, _, _)| device == &device_id) {
+                    self.notification_reply = None;
+                }
+            }
+            Message::ShowNotifications(device_id) => {
+                // The card lists the notifications; open the popup on it
+                self.expanded_device = Some(device_id);
+                if self.popup.is_none() {
+                    return self.update(Message::TogglePopup);
+                }
+            }
+            Message::DismissNotification(device_id, id) => {
+                let device = device_id.clone();
+                return Command::perform(
+                    dbus::dismiss_notification(device, id),
+                    move |result| action_done(device_id, result)
+                );
+            }
+            Message::NotificationAction(device_id, key, action) => {
+                let device = device_id.clone();
+                return Command::perform(
+                    dbus::send_notification_action(device, key, action),
+                    move |result| action_done(device_id, result)
+                );
+            }
+            Message::ToggleNotificationReply(device_id, id) => {
+                if self.notification_reply.as_ref().is_some_and(|(device, notification, _)| device == &device_id && notification == &id) {
+                    self.notification_reply = None;
+                } else {
+                    self.notification_reply = Some((device_id, id, String::new()));
+                }
+            }
+            Message::NotificationReplyChanged(draft) => {
+                if let Some((_, _, text)) = self.notification_reply.as_mut() {
+                    *text = draft;
+                }
+            }
+            Message::SendNotificationReply => {
+                if let Some((device_id, id, text)) = self.notification_reply.take_if(|(_, _, text)| !text.trim().is_empty()) {
+                    let device = device_id.clone();
+                    return Command::perform(
+                        dbus::reply_to_notification(device, id, text),
+                        move |result| action_done(device_id, result)
+                    );
+                }
+            }
+            Message::ToggleNotificationMirroring(device_id, enabled) => {
+                if let Err(e) = phone_notifications::set_mirrored(&mut self.mirrored_notifications, &device_id, enabled) {
+                    eprintln!("Failed to save notification mirroring: {}", e);
+                }
+            }
+            Message::ToggleShareText(device_id) => {
+                if self.share_text.as_ref().is_some_and(|(id, _)| id == &device_id) {
+                    self.share_text = None;
+                } else {
+                    self.share_text = Some((device_id, String::new()));
+                }
+            }
+            Message::ShareTextChanged(draft) => {
+                if let Some((_, text)) = self.share_text.as_mut() {
+                    *text = draft;
+                }
+            }
+            Message::SendShareText => {
+                if let Some((device_id, text)) = self.share_text.take_if(|(_, text)| !text.trim().is_empty()) {
+                    return self.update(share_message(device_id, text));
+                }
+            }
+            Message::DropTargetChanged(device_id) => {
+                self.drop_target = device_id;
+            }
+            Message::ItemsDropped(device_id, items) => {
+                self.drop_target = None;
+                let id = device_id.clone();
+                
+                return match items {
+                    ui::dnd::DroppedItems::Uris(uris) => {
+                        let (paths, urls): (Vec<_>, Vec<_>) = uris.into_iter().partition(|uri| uri.starts_with('/'));
+                        Command::perform(
+                            async move {
+                                let mut files = tokio::task::spawn_blocking(move || ui::dnd::expand_folders(paths))
+                                    .await
+                                    .unwrap_or_default();
+                                files.extend(urls);
+                                dbus::share_files(id, files).await
+                            },
+                            move |result| action_done(device_id, result)
+                        )
+                    }
+                    ui::dnd::DroppedItems::Text(text) => self.update(share_message(id, text)),
+                };
+            }
             Message::MediaPlayerSelected(ref device_id, ref player_name) => {
                 let id = device_id.clone();
                 let player = player_name.clone();
                 let id_for_refresh = device_id.clone();
                 return Command::batch(vec![
                     Command::perform(
-                        async move {
-                            dbus::set_media_player(id, player).await;
-                        },
-                        |_| Action::App(Message::RefreshDevices)
+                        dbus::set_media_player(id.clone(), player),
+                        move |result| action_done(id, result)
                     ),
                     Command::perform(
                         async move {
                             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await;
+                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await.ok();
                             (id_for_refresh, info)
                         },
                         |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
@@ -473,6 +876,7 @@ impl cosmic::Application for KdeConnectApplet {
                 if let Some(device) = self.devices.get_mut(device_id) {
                     device.media_info = info.clone();
                     if let Some(info) = info {
+                        self.mpris.media_info(device_id, info);
                         if !info.player.is_empty() {
                             device.current_player = Some(info.player.clone());
                         }
@@ -483,7 +887,7 @@ impl cosmic::Application for KdeConnectApplet {
                 let id = device_id.clone();
                 return Command::perform(
                     async move {
-                        let info = dbus::get_media_player_info(id.clone()).await;
+                        let info = dbus::get_media_player_info(id.clone()).await.ok();
                         (id, info)
                     },
                     |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
@@ -495,14 +899,14 @@ impl cosmic::Application for KdeConnectApplet {
                 return Command::batch(vec![
                     Command::perform(
                         async move {
-                            let players = dbus::get_media_player_list(id.clone()).await;
+                            let players = dbus::get_media_player_list(id.clone()).await.unwrap_or_default();
                             (id, players)
                         },
                         |(device_id, players)| Action::App(Message::MediaPlayersUpdated(device_id, players))
                     ),
                     Command::perform(
                         async move {
-                            let info = dbus::get_media_player_info(id_for_info.clone()).await;
+                            let info = dbus::get_media_player_info(id_for_info.clone()).await.ok();
                             (id_for_info, info)
                         },
                         |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
@@ -514,33 +918,116 @@ impl cosmic::Application for KdeConnectApplet {
                     device.available_players = players.clone();
                 }
             }
+            Message::MediaSeekDragged(device_id, position) => {
+                self.seek_drag = Some((device_id, position));
+            }
+            Message::MediaSeekReleased(ref device_id) => {
+                let Some((drag_id, position)) = self.seek_drag.take() else {
+                    return Command::none();
+                };
+                if &drag_id != device_id {
+                    return Command::none();
+                }
+                
+                // Jump locally so the bar doesn't snap back until the phone confirms
+                if let Some(info) = self.devices.get_mut(device_id).and_then(|d| d.media_info.as_mut()) {
+                    info.position = position;
+                    info.fetched_at = Some(std::time::Instant::now());
+                }
+                
+                let id = device_id.clone();
+                let id_for_refresh = device_id.clone();
+                return Command::batch(vec![
+                    Command::perform(
+                        dbus::set_media_position(id.clone(), position.clamp(0, i32::MAX as i64) as i32),
+                        move |result| action_done(id, result)
+                    ),
+                    Command::perform(
+                        async move {
+                            tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+                            let info = dbus::get_media_player_info(id_for_refresh.clone()).await.ok();
+                            (id_for_refresh, info)
+                        },
+                        |(device_id, info)| Action::App(Message::MediaInfoUpdated(device_id, info))
+                    )
+                ]);
+            }
+            Message::MediaTick => {
+                // Nothing to update; the view reads the position from the clock
+            }
             Message::OpenSettings => {
                 let _ = std::process::Command::new("cosmic-connect-settings")
                     .spawn();
             }
             Message::RemoteInput(ref device_id) => {
-                eprintln!("Remote input requested for device: {}", device_id);
-            }
-            Message::PresenterMode(ref device_id) => {
-                eprintln!("Presenter mode requested for device: {}", device_id);
+                let device_name = self.devices.get(device_id)
+                    .map(|d| d.name.clone())
+                    .unwrap_or_else(|| "Unknown Device".to_string());
+                
+                let id = device_id.clone();
+                let name = device_name;
+                
+                std::thread::spawn(move || {
+                    let _ = std::process::Command::new("cosmic-connect-remote-input")
+                        .arg(&id)
+                        .arg(&name)
+                        .spawn();
+                });
             }
             Message::UseAsMonitor(ref device_id) => {
-                eprintln!("Use as monitor requested for device: {}", device_id);
+                let id = device_id.clone();
+                return Command::perform(
+                    dbus::start_virtual_monitor(id.clone()),
+                    move |result| match result {
+                        Ok(()) => Action::App(Message::MonitorStateChanged(id, true)),
+                        Err(e) => Action::App(Message::ActionFailed(id, e.to_string())),
+                    }
+                );
+            }
+            Message::StopMonitor(ref device_id) => {
+                let id = device_id.clone();
+                return Command::perform(
+                    dbus::stop_virtual_monitor(id.clone()),
+                    move |result| match result {
+                        Ok(()) => Action::App(Message::MonitorStateChanged(id, false)),
+                        Err(e) => Action::App(Message::ActionFailed(id, e.to_string())),
+                    }
+                );
             }
-            Message::PairingRequestReceived(device_id, device_name, device_type) => {
+            Message::MonitorStateChanged(device_id, active) => {
+                if active {
+                    self.toasts.remove(&device_id);
+                    self.virtual_displays.insert(device_id);
+                } else {
+                    self.virtual_displays.remove(&device_id);
+                }
+            }
+            Message::PairingRequestReceived(device_id, device_name, device_type, verification_key) => {
                 eprintln!("=== Pairing Request in Main App ===");
                 eprintln!("Device: {} ({})", device_name, device_id);
                 eprintln!("Type: {}", device_type);
                 
                 tokio::spawn(async move {
-                    if let Err(e) = notifications::show_pairing_notification(&device_name, &device_id).await {
+                    if let Err(e) = notifications::show_pairing_notification(
+                        &device_name,
+                        &device_id,
+                        verification_key.as_deref(),
+                    ).await {
                         eprintln!("Failed to show notification: {}", e);
                     }
                 });
                 
-                return Command::perform(dbus::fetch_devices(), |devices| {
-                    Action::App(Message::DevicesUpdated(devices))
-                });
+                return Command::perform(dbus::fetch_devices(), devices_fetched);
+            }
+            Message::ActionFailed(device_id, error) => {
+                self.toasts.insert(device_id.clone(), error);
+                return Command::perform(
+                    tokio::time::sleep(TOAST_TIMEOUT),
+                    move |_| Action::App(Message::DismissToast(device_id))
+                );
+            }
+            Message::DismissToast(ref device_id) => {
+                self.toasts.remove(device_id);
             }
         }
         Command::none()
@@ -559,7 +1046,27 @@ impl cosmic::Application for KdeConnectApplet {
             return widget::text("").into();
         }
         
-        ui::popup::create_popup_view(&self.devices, self.expanded_device.as_ref(), self.expanded_player_menu.as_ref())
+        ui::popup::create_popup_view(&ui::popup::PopupState {
+            devices: &self.devices,
+            expanded_device: self.expanded_device.as_ref(),
+            expanded_player_menu: self.expanded_player_menu.as_ref(),
+            toasts: &self.toasts,
+            daemon_status: self.daemon_status,
+            seek_drag: self.seek_drag.as_ref(),
+            virtual_displays: &self.virtual_displays,
+            mounts: &self.mounts,
+            active_transfers: &self.active_transfers,
+            transfer_history: &self.transfer_history,
+            drop_target: self.drop_target.as_ref(),
+            share_text: self.share_text.as_ref(),
+            recent_shares: &self.recent_shares,
+            clipboard_history: &self.clipboard_history,
+            expanded_clipboard: self.expanded_clipboard.as_ref(),
+            expanded_transfers: self.expanded_transfers.as_ref(),
+            phone_notifications: &self.phone_notifications,
+            notification_reply: self.notification_reply.as_ref(),
+            mirrored_notifications: &self.mirrored_notifications,
+        })
     }
     
     fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
@@ -590,16 +1097,209 @@ impl cosmic::Application for KdeConnectApplet {
             notification.device_id,
             notification.device_name,
             notification.device_type,
+            notification.verification_key,
         ));
         
-        Subscription::batch(vec![
-            cosmic::iced::time::every(std::time::Duration::from_secs(5))
-                .map(|_| Message::RefreshDevices),
+        // Device list is kept current by kdeconnectd's signals, not by polling
+        let device_sub = Subscription::run_with_id("device-state", device_state::watch())
+            .map(|event| match event {
+                device_state::DeviceEvent::Snapshot(devices) => Message::DevicesUpdated(devices),
+                device_state::DeviceEvent::Updated(device) => Message::DeviceUpdated(device),
+                device_state::DeviceEvent::Removed(device_id) => Message::DeviceRemoved(device_id),
+                device_state::DeviceEvent::Daemon(status) => Message::DaemonStatusChanged(status),
+                device_state::DeviceEvent::MediaChanged(device_id) => Message::RequestMediaInfo(device_id),
+            });
+        
+        let transfer_sub = Subscription::run_with_id("transfers", transfers::watch())
+            .map(|event| match event {
+                transfers::TransferEvent::Changed(transfer) => Message::TransferChanged(transfer),
+                transfers::TransferEvent::Ended(transfer) => Message::TransferEnded(transfer),
+            });
+        
+        let clipboard_sub = Subscription::run_with_id("clipboard", clipboard_watch::watch())
+            .map(Message::ClipboardChanged);
+        
+        let mut subscriptions = vec![
+            device_sub,
             pairing_sub,
-        ])
+            transfer_sub,
+            clipboard_sub,
+        ];
+        
+        // kdeconnectd unmounts on its own, e.g. when the phone's SFTP server stops
+        for (device_id, state) in &self.mounts {
+            if let sftp::MountState::Mounted(_) | sftp::MountState::Stale = state {
+                subscriptions.push(
+                    Subscription::run_with_id(("sftp", device_id.clone()), sftp::watch(device_id.clone()))
+                        .map(|(device_id, state)| Message::MountStateChanged(device_id, state))
+                );
+            }
+        }
+        
+        // Notifications are watched as long as the device is connected, not just while the popup is open;
+        // turning mirroring on or off restarts the watch
+        for device in self.devices.values() {
+            if device.is_paired && device.is_reachable && device.plugins.is_loaded(Plugin::Notifications) {
+                let mirror = self.mirrored_notifications.contains(&device.id);
+                subscriptions.push(
+                    Subscription::run_with_id(("notifications", device.id.clone(), mirror), phone_notifications::watch(device.id.clone(), mirror))
+                        .map(|event| match event {
+                            phone_notifications::NotificationEvent::Posted(device_id, notification) => Message::NotificationPosted(device_id, notification),
+                            phone_notifications::NotificationEvent::Removed(device_id, id) => Message::NotificationRemoved(device_id, id),
+                            phone_notifications::NotificationEvent::Cleared(device_id) => Message::NotificationsCleared(device_id),
+                            phone_notifications::NotificationEvent::Opened(device_id) => Message::ShowNotifications(device_id),
+                        })
+                );
+            }
+        }
+        
+        // Keep the seek bars moving while something plays and the popup is open
+        let is_playing = self.devices.values()
+            .any(|d| d.media_info.as_ref().is_some_and(|info| info.is_playing));
+        if self.popup.is_some() && is_playing {
+            subscriptions.push(
+                cosmic::iced::time::every(tokio::time::Duration::from_secs(1)).map(|_| Message::MediaTick)
+            );
+        }
+        
+        Subscription::batch(subscriptions)
     }
 }
 
+impl KdeConnectApplet {
+    /// The paired device a transfer's job view talks about
+    fn transfer_device(&self, transfer: &transfers::Transfer) -> Option<String> {
+        self.devices.values()
+            .filter(|d| d.is_paired)
+            .find(|d| transfer.mentions(&d.name))
+            .map(|d| d.id.clone())
+    }
+    
+    /// Drop the mount of a device that went away, unmounting it so no stale
+    /// sshfs mount is left behind
+    fn forget_mount(&mut self, device_id: &str) {
+        if self.mounts.remove(device_id).is_some_and(|state| !matches!(state, sftp::MountState::Failed(_))) {
+            let id = device_id.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = sftp::unmount(id).await {
+                    eprintln!("Failed to unmount disconnected device: {}", e);
+                }
+            });
+        }
+    }
+    
+    /// Pick up mounts of these devices the applet didn't make itself, such as
+    /// ones left from before it started or made by another KDE Connect client
+    fn find_mounts(&self, device_ids: Vec<String>) -> Command<Message> {
+        let unknown = device_ids.into_iter().filter(|id| {
+            !self.mounts.contains_key(id)
+                && self.devices.get(id).is_some_and(|d| d.is_reachable && d.plugins.is_loaded(Plugin::Sftp))
+        });
+        Command::batch(unknown.map(|device_id| {
+            let id = device_id.clone();
+            Command::perform(sftp::state(id), move |state| match state {
+                sftp::MountState::Mounted(_) | sftp::MountState::Stale => {
+                    Action::App(Message::MountStateChanged(device_id, state))
+                }
+                _ => Action::None,
+            })
+        }))
+    }
+    
+    fn remember_share(&mut self, content: &str) {
+        if let Err(e) = shares::remember(&mut self.recent_shares, content) {
+            eprintln!("Failed to save recent shares: {}", e);
+        }
+    }
+}
+
+/// Let the user pick where a renamed received file goes instead, and move it
+/// there. Gives the path it ends up at.
+async fn save_received_as(path: std::path::PathBuf, wanted_name: String) -> Result<std::path::PathBuf, String> {
+    let folder = path.parent().map(|p| p.to_string_lossy().into_owned());
+    let title = format!("{} already exists - save received file as", wanted_name);
+    
+    let Some(chosen) = portal::save_file(title, wanted_name, folder, None).await else {
+        return Ok(path);
+    };
+    let dest = std::path::PathBuf::from(chosen);
+    if dest == path {
+        return Ok(path);
+    }
+    
+    // The dialog already asked before replacing an existing file
+    if tokio::fs::rename(&path, &dest).await.is_err() {
+        // Another filesystem
+        let moved = match tokio::fs::copy(&path, &dest).await {
+            Ok(_) => tokio::fs::remove_file(&path).await,
+            Err(e) => Err(e),
+        };
+        moved.map_err(|e| format!("Couldn't move {} to {}: {}", path.display(), dest.display(), e))?;
+    }
+    Ok(dest)
+}
+
+/// The devices whose clipboard settings share a local copy automatically.
+///
+/// The settings app may have changed them since the last copy, so they are
+/// read again each time, off the UI thread.
+async fn auto_share_targets(devices: Vec<String>, is_password: bool) -> Vec<String> {
+    tokio::task::spawn_blocking(move || {
+        devices.into_iter()
+            .filter(|device_id| {
+                let config = plugin_config::ClipboardPluginConfig::load(device_id).unwrap_or_default();
+                config.auto_share && (config.send_password || !is_password)
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// A lone link opens on the phone; other text is offered to copy
+fn share_message(device_id: String, text: String) -> Message {
+    match shares::link(&text) {
+        Some(url) => Message::ShareUrl(device_id, url.to_string()),
+        None => Message::ShareText(device_id, text),
+    }
+}
+
+/// Show why an action failed on the device card. Whatever a successful one
+/// changed arrives through the device state service's signals.
+fn action_done(device_id: String, result: Result<(), dbus::KdeConnectError>) -> Action<Message> {
+    match result {
+        Ok(()) => Action::None,
+        Err(e) => Action::App(Message::ActionFailed(device_id, e.to_string())),
+    }
+}
+
+/// Replace the device list with a full reload; a failed one keeps the current list
+fn devices_fetched(result: Result<Vec<Device>, dbus::KdeConnectError>) -> Action<Message> {
+    match result {
+        Ok(devices) => Action::App(Message::DevicesUpdated(devices)),
+        Err(e) => {
+            eprintln!("Failed to reload devices: {}", e);
+            Action::None
+        }
+    }
+}
+
+/// Show why a file or folder could not be opened on the device's card
+fn opened(device_id: String, result: std::io::Result<()>) -> Action<Message> {
+    match result {
+        Ok(()) => Action::None,
+        Err(e) => Action::App(Message::ActionFailed(device_id, format!("Could not open: {}", e))),
+    }
+}
+
+/// Media state is fetched separately from the device itself; carry it over
+/// when a fresh copy of the device replaces the old one.
+fn keep_media_state(device: &mut Device, old_device: &Device) {
+    device.available_players = old_device.available_players.clone();
+    device.current_player = old_device.current_player.clone();
+    device.media_info = old_device.media_info.clone();
+}
+
 impl Drop for KdeConnectApplet {
     fn drop(&mut self) {
         eprintln!("=== KdeConnectApplet Drop called ===");
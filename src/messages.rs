@@ -7,6 +7,10 @@ pub enum Message {
     // Device management
     RefreshDevices,
     DevicesUpdated(Vec<crate::models::Device>),
+    DeviceUpdated(crate::models::Device),
+    DeviceRemoved(String), // device_id
+    DaemonStatusChanged(crate::dbus::DaemonStatus),
+    StartDaemon,
     PairDevice(String),
     UnpairDevice(String),
     AcceptPairing(String),
@@ -20,11 +24,46 @@ pub enum Message {
     PingDevice(String),
     SendSMS(String),
     ShareClipboard(String),
+    SendClipboardItem(String, crate::clipboard::ClipboardItem), // device_id, item
+    SendClipboardImage(String, crate::clipboard::ClipboardImage), // device_id, picture
+    CopyClipboardItem(crate::clipboard::ClipboardItem),
+    ClipboardRead(Option<crate::clipboard::ClipboardItem>),
+    ClipboardChanged(crate::clipboard::ClipboardItem), // copied locally, seen in the background
+    AutoShareClipboard(Vec<String>, String), // device_ids whose clipboard settings share it, text
+    ToggleClipboardHistory(String), // device_id
+    
+    // Phone notifications
+    NotificationPosted(String, crate::phone_notifications::PhoneNotification), // device_id, new or changed notification
+    NotificationRemoved(String, String), // device_id, notification id
+    NotificationsCleared(String), // device_id
+    ShowNotifications(String), // device_id whose mirrored popup was clicked
+    DismissNotification(String, String), // device_id, notification id
+    NotificationAction(String, String, String), // device_id, notification key, action
+    ToggleNotificationReply(String, String), // device_id, notification id
+    NotificationReplyChanged(String),
+    SendNotificationReply,
+    ToggleNotificationMirroring(String, bool), // device_id, show as desktop notifications
     
     // File operations
     SendFile(String),
     ShareUrl(String, String),
+    ShareText(String, String), // device_id, text
+    ToggleShareText(String), // device_id
+    ShareTextChanged(String),
+    SendShareText,
+    DropTargetChanged(Option<String>), // device_id under a drag
+    ItemsDropped(String, crate::ui::dnd::DroppedItems), // device_id, payload
     BrowseDevice(String),
+    UnmountDevice(String),
+    MountStateChanged(String, crate::sftp::MountState), // device_id, state
+    TransferChanged(crate::transfers::Transfer),
+    TransferEnded(crate::transfers::Transfer),
+    CancelTransfer(u32), // transfer id
+    OpenTransferFile(String, std::path::PathBuf), // device_id, path
+    OpenTransferWith(String, std::path::PathBuf), // device_id, path
+    OpenTransferFolder(String, std::path::PathBuf), // device_id, path
+    ToggleTransferHistory(String), // device_id
+    ReceivedFileMoved(std::path::PathBuf, std::path::PathBuf), // old path, new path
     
     // Remote control
     RemoteInput(String),
@@ -44,14 +83,22 @@ pub enum Message {
     RequestMediaInfo(String), // device_id
     RefreshMediaPlayers(String), // device_id
     MediaPlayersUpdated(String, Vec<String>), // device_id, players
+    MediaSeekDragged(String, i64), // device_id, position (ms)
+    MediaSeekReleased(String), // device_id
+    MediaTick, // Redraw the interpolated playback position
     
     // Advanced features
-    PresenterMode(String),
     UseAsMonitor(String),
+    StopMonitor(String),
+    MonitorStateChanged(String, bool), // device_id, virtual display running
     
     // Settings
     OpenSettings,
     
     // Pairing notifications
-    PairingRequestReceived(String, String, String), // device_id, device_name, device_type
+    PairingRequestReceived(String, String, String, Option<String>), // device_id, device_name, device_type, verification_key
+    
+    // Action feedback
+    ActionFailed(String, String), // device_id, error
+    DismissToast(String), // device_id
 }
\ No newline at end of file
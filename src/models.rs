@@ -1,4 +1,286 @@
 // src/models.rs
+use std::collections::HashSet;
+
+/// Pairing state as reported by the daemon's `pairState` property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PairState {
+    #[default]
+    NotPaired,
+    /// We asked the device to pair and are waiting for it
+    Requested,
+    /// The device asked us to pair
+    RequestedByPeer,
+    Paired,
+}
+
+impl PairState {
+    pub fn from_daemon(state: i32) -> Self {
+        match state {
+            1 => Self::Requested,
+            2 => Self::RequestedByPeer,
+            3 => Self::Paired,
+            _ => Self::NotPaired,
+        }
+    }
+
+    /// A pairing attempt is under way in either direction
+    pub fn is_pending(self) -> bool {
+        matches!(self, Self::Requested | Self::RequestedByPeer)
+    }
+}
+
+/// A KDE Connect plugin known to this app.
+///
+/// Everything that depends on the plugin's identity lives in [`Plugin::info`],
+/// so supporting a new plugin means adding a variant and its table entry. The
+/// object path of a plugin that exports one belongs to its proxy's
+/// `DeviceObject` impl in `proxies.rs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Plugin {
+    Battery,
+    Bigscreen,
+    Clipboard,
+    ConnectivityReport,
+    Contacts,
+    FindMyPhone,
+    RunCommand,
+    ScreensaverInhibit,
+    LockDevice,
+    Telephony,
+    MprisRemote,
+    MprisControl,
+    Mousepad,
+    PauseMusic,
+    Photo,
+    Ping,
+    Presenter,
+    Notifications,
+    RemoteControl,
+    Sftp,
+    RemoteKeyboard,
+    Share,
+    SendNotifications,
+    Sms,
+    VirtualMonitor,
+    SystemVolume,
+}
+
+/// Static description of a plugin
+#[derive(Debug)]
+#[allow(dead_code)] // name and description are only shown by the settings app
+pub struct PluginInfo {
+    /// Plugin ID used by kdeconnectd, e.g. `kdeconnect_ping`
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+impl Plugin {
+    /// Every plugin, in display order
+    pub const ALL: [Plugin; 26] = [
+        Plugin::Battery,
+        Plugin::Bigscreen,
+        Plugin::Clipboard,
+        Plugin::ConnectivityReport,
+        Plugin::Contacts,
+        Plugin::FindMyPhone,
+        Plugin::RunCommand,
+        Plugin::ScreensaverInhibit,
+        Plugin::LockDevice,
+        Plugin::Telephony,
+        Plugin::MprisRemote,
+        Plugin::MprisControl,
+        Plugin::Mousepad,
+        Plugin::PauseMusic,
+        Plugin::Photo,
+        Plugin::Ping,
+        Plugin::Presenter,
+        Plugin::Notifications,
+        Plugin::RemoteControl,
+        Plugin::Sftp,
+        Plugin::RemoteKeyboard,
+        Plugin::Share,
+        Plugin::SendNotifications,
+        Plugin::Sms,
+        Plugin::VirtualMonitor,
+        Plugin::SystemVolume,
+    ];
+
+    pub fn info(self) -> &'static PluginInfo {
+        match self {
+            Plugin::Battery => &PluginInfo {
+                id: "kdeconnect_battery",
+                name: "Battery monitor",
+                description: "Show your phone battery next to your computer battery",
+            },
+            Plugin::Bigscreen => &PluginInfo {
+                id: "kdeconnect_bigscreen",
+                name: "Bigscreen voice control",
+                description: "Send voice commands to your TV running Plasma Bigscreen",
+            },
+            Plugin::Clipboard => &PluginInfo {
+                id: "kdeconnect_clipboard",
+                name: "Clipboard",
+                description: "Share the clipboard between devices",
+            },
+            Plugin::ConnectivityReport => &PluginInfo {
+                id: "kdeconnect_connectivity_report",
+                name: "Connectivity monitor",
+                description: "Show your phone's network signal strength",
+            },
+            Plugin::Contacts => &PluginInfo {
+                id: "kdeconnect_contacts",
+                name: "Contacts",
+                description: "Synchronize Contacts from the Connected Device to the Desktop",
+            },
+            Plugin::FindMyPhone => &PluginInfo {
+                id: "kdeconnect_findmyphone",
+                name: "Find this device",
+                description: "Find this device by making it play an alarm sound",
+            },
+            Plugin::RunCommand => &PluginInfo {
+                id: "kdeconnect_runcommand",
+                name: "Host remote commands",
+                description: "Trigger commands predefined on the remote device",
+            },
+            Plugin::ScreensaverInhibit => &PluginInfo {
+                id: "kdeconnect_screensaver_inhibit",
+                name: "Inhibit screensaver",
+                description: "Inhibit the screensaver when the device is connected",
+            },
+            Plugin::LockDevice => &PluginInfo {
+                id: "kdeconnect_lockdevice",
+                name: "LockDevice",
+                description: "Locks your systems",
+            },
+            Plugin::Telephony => &PluginInfo {
+                id: "kdeconnect_telephony",
+                name: "ModemManager Telephony integration",
+                description: "Show notifications for incoming calls",
+            },
+            Plugin::MprisRemote => &PluginInfo {
+                id: "kdeconnect_mprisremote",
+                name: "MprisRemote",
+                description: "Control MPRIS services",
+            },
+            Plugin::MprisControl => &PluginInfo {
+                id: "kdeconnect_mpriscontrol",
+                name: "Multimedia control receiver",
+                description: "Remote control your music and videos",
+            },
+            Plugin::Mousepad => &PluginInfo {
+                id: "kdeconnect_mousepad",
+                name: "Mousepad",
+                description: "Use your phone as a wireless mouse and keyboard",
+            },
+            Plugin::PauseMusic => &PluginInfo {
+                id: "kdeconnect_pausemusic",
+                name: "Pause media during calls",
+                description: "Pause music/videos during a phone call",
+            },
+            Plugin::Photo => &PluginInfo {
+                id: "kdeconnect_photo",
+                name: "Photo",
+                description: "Use a connected device to take a photo",
+            },
+            Plugin::Ping => &PluginInfo {
+                id: "kdeconnect_ping",
+                name: "Ping",
+                description: "Send and receive pings",
+            },
+            Plugin::Presenter => &PluginInfo {
+                id: "kdeconnect_presenter",
+                name: "Presenter",
+                description: "Use your mobile device to point to things on the screen",
+            },
+            Plugin::Notifications => &PluginInfo {
+                id: "kdeconnect_notifications",
+                name: "Receive notifications",
+                description: "Show device's notifications on this computer and keep them in sync",
+            },
+            Plugin::RemoteControl => &PluginInfo {
+                id: "kdeconnect_remotecontrol",
+                name: "Remote control",
+                description: "Control system volume and multimedia players remotely",
+            },
+            Plugin::Sftp => &PluginInfo {
+                id: "kdeconnect_sftp",
+                name: "Remote filesystem browser",
+                description: "Browse files on the device remotely using SFTP",
+            },
+            Plugin::RemoteKeyboard => &PluginInfo {
+                id: "kdeconnect_remotekeyboard",
+                name: "Remote keypresses",
+                description: "Receive remote keyboard input",
+            },
+            Plugin::Share => &PluginInfo {
+                id: "kdeconnect_share",
+                name: "Share",
+                description: "Send and receive files",
+            },
+            Plugin::SendNotifications => &PluginInfo {
+                id: "kdeconnect_sendnotifications",
+                name: "Send notifications",
+                description: "Send notifications to your phone from this computer",
+            },
+            Plugin::Sms => &PluginInfo {
+                id: "kdeconnect_sms",
+                name: "SMS Messages",
+                description: "Send and receive SMS messages",
+            },
+            Plugin::VirtualMonitor => &PluginInfo {
+                id: "kdeconnect_virtualmonitor",
+                name: "Virtual monitor",
+                description: "Use your phone as a virtual monitor",
+            },
+            Plugin::SystemVolume => &PluginInfo {
+                id: "kdeconnect_systemvolume",
+                name: "Volume control",
+                description: "Control device volume remotely",
+            },
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        self.info().id
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|plugin| plugin.id() == id)
+    }
+}
+
+/// Plugin availability for one device
+#[derive(Debug, Clone, Default)]
+pub struct PluginSet {
+    /// Announced by the peer
+    pub supported: HashSet<Plugin>,
+    /// Switched on in the local configuration
+    pub enabled: HashSet<Plugin>,
+    /// Running for this device right now
+    pub loaded: HashSet<Plugin>,
+}
+
+impl PluginSet {
+    #[allow(dead_code)] // Used by the settings app
+    pub fn is_supported(&self, plugin: Plugin) -> bool {
+        self.supported.contains(&plugin)
+    }
+
+    pub fn is_enabled(&self, plugin: Plugin) -> bool {
+        self.enabled.contains(&plugin)
+    }
+
+    /// Whether the plugin can be used, which needs it to be loaded
+    pub fn is_loaded(&self, plugin: Plugin) -> bool {
+        self.loaded.contains(&plugin)
+    }
+
+    pub fn any_loaded(&self, plugins: &[Plugin]) -> bool {
+        plugins.iter().any(|plugin| self.is_loaded(*plugin))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Device {
     pub id: String,
@@ -6,22 +288,12 @@ pub struct Device {
     pub device_type: String,
     pub is_reachable: bool,
     pub is_paired: bool,
+    pub pair_state: PairState,
     pub battery_level: Option<i32>,
     pub is_charging: Option<bool>,
-    pub has_battery: bool,
-    pub has_ping: bool,
-    pub has_share: bool,
-    pub has_findmyphone: bool,
-    pub has_sms: bool,
-    pub has_clipboard: bool,
-    pub has_contacts: bool,
-    pub has_mpris: bool,
-    pub has_remote_keyboard: bool,
-    pub has_sftp: bool,
-    pub has_presenter: bool,
-    pub has_lockdevice: bool,
-    pub has_virtualmonitor: bool,
-    pub pairing_requests: i32,
+    pub plugins: PluginSet,
+    /// Key to compare with the one shown on the device while pairing is pending
+    pub verification_key: Option<String>,
     // Connectivity information
     pub signal_strength: Option<i32>,  // 0-4 bars, or -1 for no signal
     pub network_type: Option<String>,  // "5G", "4G", "3G", "2G", etc.
@@ -0,0 +1,501 @@
+// src/mpris.rs
+//! MPRIS2 bridge for the phone's media player.
+//!
+//! Every paired, reachable device with the `mprisremote` plugin loaded is
+//! published as `org.mpris.MediaPlayer2.CosmicConnect.<device>`, so COSMIC's
+//! media controls, media keys and `playerctl` can drive the phone like any
+//! local player. Each device gets its own session bus connection because
+//! MPRIS fixes the object path to `/org/mpris/MediaPlayer2`.
+//!
+//! The published state is refreshed from every [`MediaPlayerInfo`] the applet
+//! fetches, which includes a fetch after each `propertiesChanged` from the
+//! `mprisremote` plugin.
+//!
+//! Recent kdeconnectd versions export the phone's players themselves, as
+//! `org.mpris.MediaPlayer2.kdeconnect.*`. While any such name is on the bus
+//! nothing is published here, so a player doesn't show up twice.
+
+use std::collections::{HashMap, HashSet};
+
+use futures::StreamExt;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{fdo, interface, Connection};
+
+use crate::dbus::{self, KdeConnectError, MediaPlayerInfo};
+use crate::models::{Device, Plugin};
+
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const BUS_NAME_PREFIX: &str = "org.mpris.MediaPlayer2.CosmicConnect";
+/// Players kdeconnectd exports for the phone on its own
+const DAEMON_PLAYER_PREFIX: &str = "org.mpris.MediaPlayer2.kdeconnect.";
+
+/// A position report further than this from the expected position is a seek
+const SEEK_TOLERANCE_MS: i64 = 1500;
+
+enum Command {
+    /// The full device list; anything not in it is unpublished
+    Sync(Vec<Device>),
+    Updated(Device),
+    Removed(String),
+    MediaInfo(String, MediaPlayerInfo),
+}
+
+/// Handle to the background task that owns the published players.
+pub struct Bridge {
+    tx: UnboundedSender<Command>,
+}
+
+impl Bridge {
+    pub fn start() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run(rx));
+        Self { tx }
+    }
+
+    pub fn sync<'a>(&self, devices: impl IntoIterator<Item = &'a Device>) {
+        let _ = self.tx.send(Command::Sync(devices.into_iter().cloned().collect()));
+    }
+
+    pub fn device_updated(&self, device: &Device) {
+        let _ = self.tx.send(Command::Updated(device.clone()));
+    }
+
+    pub fn device_removed(&self, device_id: &str) {
+        let _ = self.tx.send(Command::Removed(device_id.to_string()));
+    }
+
+    pub fn media_info(&self, device_id: &str, info: &MediaPlayerInfo) {
+        let _ = self.tx.send(Command::MediaInfo(device_id.to_string(), info.clone()));
+    }
+}
+
+async fn run(mut rx: UnboundedReceiver<Command>) {
+    // Dropping a device's connection releases its bus name
+    let mut published: HashMap<String, Connection> = HashMap::new();
+    let mut devices: HashMap<String, Device> = HashMap::new();
+
+    // Without a way to see kdeconnectd's own players, the bridge stays on
+    let (mut daemon_players, mut name_changes) = match watch_daemon_players().await {
+        Ok((names, changes)) => (names, Some(changes)),
+        Err(e) => {
+            eprintln!("MPRIS: can't look for kdeconnectd's players: {:?}", e);
+            (HashSet::new(), None)
+        }
+    };
+
+    loop {
+        let command = tokio::select! {
+            command = rx.recv() => match command {
+                Some(command) => command,
+                None => break,
+            },
+            Some(change) = async { name_changes.as_mut()?.next().await }, if name_changes.is_some() => {
+                let Ok(args) = change.args() else {
+                    continue;
+                };
+                if !args.name().starts_with(DAEMON_PLAYER_PREFIX) {
+                    continue;
+                }
+                let was_exporting = !daemon_players.is_empty();
+                if args.new_owner().is_some() {
+                    daemon_players.insert(args.name().to_string());
+                } else {
+                    daemon_players.remove(args.name().as_str());
+                }
+
+                match (was_exporting, daemon_players.is_empty()) {
+                    (false, false) => {
+                        eprintln!("MPRIS: kdeconnectd exports the phone's players; unpublishing ours");
+                        published.clear();
+                    }
+                    (true, true) => {
+                        for device in devices.values() {
+                            update_device(&mut published, device.clone()).await;
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+        };
+
+        let bridged = daemon_players.is_empty();
+        match command {
+            Command::Sync(list) => {
+                devices = list.into_iter().map(|d| (d.id.clone(), d)).collect();
+                published.retain(|id, _| devices.contains_key(id));
+                if bridged {
+                    for device in devices.values() {
+                        update_device(&mut published, device.clone()).await;
+                    }
+                }
+            }
+            Command::Updated(device) => {
+                devices.insert(device.id.clone(), device.clone());
+                if bridged {
+                    update_device(&mut published, device).await;
+                }
+            }
+            Command::Removed(device_id) => {
+                devices.remove(&device_id);
+                if published.remove(&device_id).is_some() {
+                    eprintln!("MPRIS: unpublished {}", device_id);
+                }
+            }
+            Command::MediaInfo(device_id, info) => {
+                if let Some(conn) = published.get(&device_id) {
+                    if let Err(e) = update_player(conn, info).await {
+                        eprintln!("MPRIS: failed to update {}: {:?}", device_id, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The names of kdeconnectd's own phone players, and a stream of bus name
+/// changes to keep them current with
+async fn watch_daemon_players() -> zbus::Result<(HashSet<String>, fdo::NameOwnerChangedStream)> {
+    let conn = dbus::get_connection().await.map_err(|e| zbus::Error::Failure(e.to_string()))?;
+    let bus = fdo::DBusProxy::new(&conn).await?;
+    // Subscribe before listing so that nothing in between is missed
+    let changes = bus.receive_name_owner_changed().await?;
+    let names = bus.list_names().await?
+        .into_iter()
+        .map(|name| name.to_string())
+        .filter(|name| name.starts_with(DAEMON_PLAYER_PREFIX))
+        .collect();
+    Ok((names, changes))
+}
+
+/// Publish or unpublish a device depending on whether its player is usable
+async fn update_device(published: &mut HashMap<String, Connection>, device: Device) {
+    let wanted = device.is_paired
+        && device.is_reachable
+        && device.plugins.is_loaded(Plugin::MprisRemote);
+
+    if !wanted {
+        if published.remove(&device.id).is_some() {
+            eprintln!("MPRIS: unpublished {}", device.id);
+        }
+        return;
+    }
+    if published.contains_key(&device.id) {
+        return;
+    }
+
+    match publish(&device).await {
+        Ok(conn) => {
+            eprintln!("MPRIS: published {} as {}", device.name, bus_name(&device.id));
+            published.insert(device.id, conn);
+        }
+        Err(e) => eprintln!("MPRIS: failed to publish {}: {:?}", device.name, e),
+    }
+}
+
+async fn publish(device: &Device) -> zbus::Result<Connection> {
+    let info = dbus::get_media_player_info(device.id.clone()).await.unwrap_or_default();
+
+    zbus::connection::Builder::session()?
+        .name(bus_name(&device.id))?
+        .serve_at(OBJECT_PATH, Root { identity: device.name.clone() })?
+        .serve_at(OBJECT_PATH, Player::new(device.id.clone(), info))?
+        .build()
+        .await
+}
+
+/// Store new player state and announce what changed
+async fn update_player(conn: &Connection, info: MediaPlayerInfo) -> zbus::Result<()> {
+    let iface = conn.object_server().interface::<_, Player>(OBJECT_PATH).await?;
+    let emitter = iface.signal_emitter();
+    let mut player = iface.get_mut().await;
+
+    let expected_position = player.info.current_position();
+    let old = std::mem::replace(&mut player.info, info);
+    let new = &player.info;
+
+    let track_changed = old.player != new.player
+        || old.title != new.title
+        || old.artist != new.artist
+        || old.album != new.album;
+    let metadata_changed = track_changed || old.length != new.length || old.album_art != new.album_art;
+    let playing_changed = old.is_playing != new.is_playing;
+    let volume_changed = old.volume != new.volume;
+    let capabilities_changed = old.can_play != new.can_play
+        || old.can_pause != new.can_pause
+        || old.can_go_next != new.can_go_next
+        || old.can_go_previous != new.can_go_previous
+        || old.can_seek != new.can_seek;
+    let seeked = !track_changed && (new.position - expected_position).abs() > SEEK_TOLERANCE_MS;
+    let position = new.position;
+
+    if track_changed {
+        player.track += 1;
+    }
+    if metadata_changed {
+        player.metadata_changed(emitter).await?;
+    }
+    if playing_changed {
+        player.playback_status_changed(emitter).await?;
+    }
+    if volume_changed {
+        player.volume_changed(emitter).await?;
+    }
+    if capabilities_changed {
+        player.can_play_changed(emitter).await?;
+        player.can_pause_changed(emitter).await?;
+        player.can_go_next_changed(emitter).await?;
+        player.can_go_previous_changed(emitter).await?;
+        player.can_seek_changed(emitter).await?;
+    }
+    if seeked {
+        Player::seeked(emitter, position * 1000).await?;
+    }
+
+    Ok(())
+}
+
+/// Well-known name for a device; IDs may contain characters a bus name can't
+fn bus_name(device_id: &str) -> String {
+    let mut element: String = device_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if element.is_empty() || element.starts_with(|c: char| c.is_ascii_digit()) {
+        element.insert(0, '_');
+    }
+    format!("{}.{}", BUS_NAME_PREFIX, element)
+}
+
+fn failed(e: KdeConnectError) -> fdo::Error {
+    fdo::Error::Failed(e.to_string())
+}
+
+/// `org.mpris.MediaPlayer2`
+struct Root {
+    identity: String,
+}
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> String {
+        self.identity.clone()
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// `org.mpris.MediaPlayer2.Player`, backed by the device's `mprisremote` plugin
+struct Player {
+    device_id: String,
+    info: MediaPlayerInfo,
+    /// Bumped on every track change, for `mpris:trackid`
+    track: u64,
+}
+
+impl Player {
+    fn new(device_id: String, info: MediaPlayerInfo) -> Self {
+        Self {
+            device_id,
+            info,
+            track: 0,
+        }
+    }
+
+    fn track_id(&self) -> OwnedObjectPath {
+        ObjectPath::from_string_unchecked(format!("/io/github/M4LC0ntent/CosmicConnect/Track/{}", self.track)).into()
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    async fn next(&self) -> fdo::Result<()> {
+        dbus::next_media(self.device_id.clone()).await.map_err(failed)
+    }
+
+    async fn previous(&self) -> fdo::Result<()> {
+        dbus::previous_media(self.device_id.clone()).await.map_err(failed)
+    }
+
+    async fn pause(&self) -> fdo::Result<()> {
+        dbus::pause_media(self.device_id.clone()).await.map_err(failed)
+    }
+
+    async fn play_pause(&self) -> fdo::Result<()> {
+        dbus::play_pause_media(self.device_id.clone()).await.map_err(failed)
+    }
+
+    async fn stop(&self) -> fdo::Result<()> {
+        dbus::stop_media(self.device_id.clone()).await.map_err(failed)
+    }
+
+    async fn play(&self) -> fdo::Result<()> {
+        dbus::play_media(self.device_id.clone()).await.map_err(failed)
+    }
+
+    /// `offset` is in microseconds
+    async fn seek(&self, offset: i64) -> fdo::Result<()> {
+        if !self.info.can_seek {
+            return Ok(());
+        }
+        let offset = (offset / 1000).clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+        dbus::seek_media(self.device_id.clone(), offset).await.map_err(failed)
+    }
+
+    /// `position` is in microseconds
+    async fn set_position(&self, track_id: ObjectPath<'_>, position: i64) -> fdo::Result<()> {
+        // The spec asks to ignore requests for another track or outside this one
+        let position = position / 1000;
+        if !self.info.can_seek
+            || track_id != *self.track_id()
+            || position < 0
+            || (self.info.length > 0 && position > self.info.length)
+        {
+            return Ok(());
+        }
+        let position = position.min(i32::MAX as i64) as i32;
+        dbus::set_media_position(self.device_id.clone(), position).await.map_err(failed)
+    }
+
+    fn open_uri(&self, _uri: &str) -> fdo::Result<()> {
+        Err(fdo::Error::NotSupported("Opening URIs is not supported".to_string()))
+    }
+
+    #[zbus(property)]
+    fn playback_status(&self) -> &str {
+        if self.info.is_playing {
+            "Playing"
+        } else if self.info.title.is_empty() {
+            "Stopped"
+        } else {
+            "Paused"
+        }
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        let mut metadata = HashMap::new();
+        let mut insert = |key: &str, value: Value<'_>| {
+            if let Ok(value) = value.try_to_owned() {
+                metadata.insert(key.to_string(), value);
+            }
+        };
+
+        insert("mpris:trackid", Value::from(self.track_id()));
+        if self.info.length > 0 {
+            insert("mpris:length", Value::from(self.info.length * 1000));
+        }
+        if !self.info.title.is_empty() {
+            insert("xesam:title", Value::from(self.info.title.as_str()));
+        }
+        if !self.info.artist.is_empty() {
+            insert("xesam:artist", Value::from(vec![self.info.artist.as_str()]));
+        }
+        if !self.info.album.is_empty() {
+            insert("xesam:album", Value::from(self.info.album.as_str()));
+        }
+        if let Some(url) = self.info.album_art.as_ref().and_then(|path| url::Url::from_file_path(path).ok()) {
+            insert("mpris:artUrl", Value::from(url.to_string()));
+        }
+
+        metadata
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        f64::from(self.info.volume) / 100.0
+    }
+
+    #[zbus(property)]
+    async fn set_volume(&mut self, volume: f64) -> fdo::Result<()> {
+        let volume = (volume.clamp(0.0, 1.0) * 100.0).round() as i32;
+        dbus::set_media_volume(self.device_id.clone(), volume).await.map_err(failed)?;
+        self.info.volume = volume;
+        Ok(())
+    }
+
+    /// In microseconds; clients are told about jumps through `Seeked`
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        self.info.current_position() * 1000
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        self.info.can_go_next
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        self.info.can_go_previous
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        self.info.can_play
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        self.info.can_pause
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        self.info.can_seek
+    }
+
+    #[zbus(property)]
+    fn can_control(&self) -> bool {
+        true
+    }
+
+    #[zbus(signal)]
+    async fn seeked(emitter: &SignalEmitter<'_>, position: i64) -> zbus::Result<()>;
+}
@@ -5,6 +5,9 @@ use tokio::sync::Mutex;
 use zbus::{Connection, MatchRule, MessageStream};
 use futures::StreamExt; // For .next() on MessageStream
 use std::error::Error as StdError;
+use crate::dbus;
+use crate::models::PairState;
+use crate::proxies::{self, DeviceProxy};
 
 /// Notification handler for KDE Connect pairing requests
 pub struct NotificationHandler {
@@ -106,38 +109,41 @@ impl NotificationHandler {
                     
                     // Get the pair state from message body
                     let body = message.body();
-                    if let Ok(pair_state) = body.deserialize::<i32>() {
+                    if let Ok(state) = body.deserialize::<i32>() {
+                        let pair_state = PairState::from_daemon(state);
                         
                         eprintln!("=== Pairing Signal Received ===");
                         eprintln!("Device ID: {}", device_id);
-                        eprintln!("Pair State: {} (0=NotPaired, 1=Requested, 2=RequestedByPeer, 3=Paired)", pair_state);
+                        eprintln!("Pair State: {:?}", pair_state);
                         
-                        // Check if this is a new pairing request from peer
-                        if pair_state == 2 {
-                            // Get device info
-                            match self.get_device_info(&device_id).await {
-                                Ok((name, device_type)) => {
-                                    eprintln!("Pairing request from: {} ({})", name, device_type);
+                        match pair_state {
+                            // A new pairing request from the peer
+                            PairState::RequestedByPeer => {
+                                // Get device info
+                                match self.get_device_info(&device_id).await {
+                                    Ok((name, device_type)) => {
+                                        eprintln!("Pairing request from: {} ({})", name, device_type);
                                     
-                                    let notification = PairingNotification {
-                                        device_id: device_id.clone(),
-                                        device_name: name,
-                                        device_type,
-                                    };
+                                        let notification = PairingNotification {
+                                            device_id: device_id.clone(),
+                                            device_name: name,
+                                            device_type,
+                                            verification_key: dbus::fetch_verification_key(&self.conn, &device_id).await,
+                                        };
                                     
-                                    // Send notification event
-                                    if let Err(e) = tx.send(notification).await {
-                                        eprintln!("Failed to send pairing notification: {}", e);
+                                        // Send notification event
+                                        if let Err(e) = tx.send(notification).await {
+                                            eprintln!("Failed to send pairing notification: {}", e);
+                                        }
+                                    }
+                                    Err(e) => {
+                                        eprintln!("Failed to get device info: {}", e);
                                     }
-                                }
-                                Err(e) => {
-                                    eprintln!("Failed to get device info: {}", e);
                                 }
                             }
-                        } else if pair_state == 3 {
-                            eprintln!("Device paired successfully");
-                        } else if pair_state == 0 {
-                            eprintln!("Device unpaired");
+                            PairState::Paired => eprintln!("Device paired successfully"),
+                            PairState::NotPaired => eprintln!("Device unpaired"),
+                            PairState::Requested => eprintln!("Waiting for the device to accept"),
                         }
                     }
                 }
@@ -179,6 +185,7 @@ impl NotificationHandler {
                                             device_id: device_id.clone(),
                                             device_name: name,
                                             device_type,
+                                            verification_key: dbus::fetch_verification_key(&self.conn, &device_id).await,
                                         };
                                         
                                         if let Err(e) = tx.send(notification).await {
@@ -204,76 +211,32 @@ impl NotificationHandler {
 
     /// Get all device IDs from KDE Connect daemon
     async fn get_all_devices(&self) -> Result<Vec<String>, Box<dyn StdError + Send + Sync>> {
-        let proxy = zbus::Proxy::new(
-            &self.conn,
-            "org.kde.kdeconnect",
-            "/modules/kdeconnect",
-            "org.kde.kdeconnect.daemon",
-        ).await?;
-
-        let devices: Vec<String> = proxy
-            .call("devices", &(false, false))
-            .await?;
+        let daemon = proxies::daemon(&self.conn).await?;
+        let devices = daemon.devices(false, false).await?;
 
         Ok(devices)
     }
 
     /// Check if a device is requesting pairing
     async fn check_pairing_request(&self, device_id: &str) -> Result<bool, Box<dyn StdError + Send + Sync>> {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        let proxy = zbus::Proxy::new(
-            &self.conn,
-            "org.kde.kdeconnect",
-            path.as_str(),
-            "org.freedesktop.DBus.Properties",
-        ).await?;
-
-        let result: zbus::zvariant::OwnedValue = proxy
-            .call(
-                "Get",
-                &("org.kde.kdeconnect.device", "isPairRequestedByPeer"),
-            )
-            .await?;
-
-        // downcast_ref returns Result<&bool, Error>, so just use the reference
-        let is_requesting: bool = match result.downcast_ref::<bool>() {
-            Ok(val) => val,  // val is already &bool, just use it
-            Err(_) => return Err("Failed to downcast isPairRequestedByPeer to bool".into()),
-        };
+        let device = proxies::device_object::<DeviceProxy>(&self.conn, device_id).await?;
+        let is_requesting = device.is_pair_requested_by_peer().await?;
 
         Ok(is_requesting)
     }
 
     /// Get device name and type
     async fn get_device_info(&self, device_id: &str) -> Result<(String, String), Box<dyn StdError + Send + Sync>> {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        let proxy = zbus::Proxy::new(
-            &self.conn,
-            "org.kde.kdeconnect",
-            path.as_str(),
-            "org.freedesktop.DBus.Properties",
-        ).await?;
+        let device = proxies::device_object::<DeviceProxy>(&self.conn, device_id).await?;
 
-        // Get device name
-        let name_result: zbus::zvariant::OwnedValue = proxy
-            .call("Get", &("org.kde.kdeconnect.device", "name"))
-            .await?;
-        
-        let name = name_result
-            .downcast_ref::<String>()
-            .map(|s| s.to_string())
+        let name = device
+            .name()
+            .await
             .unwrap_or_else(|_| "Unknown Device".to_string());
 
-        // Get device type
-        let type_result: zbus::zvariant::OwnedValue = proxy
-            .call("Get", &("org.kde.kdeconnect.device", "type"))
-            .await?;
-        
-        let device_type = type_result
-            .downcast_ref::<String>()
-            .map(|s| s.to_string())
+        let device_type = device
+            .device_type()
+            .await
             .unwrap_or_else(|_| "unknown".to_string());
 
         Ok((name, device_type))
@@ -295,6 +258,7 @@ pub struct PairingNotification {
     pub device_id: String,
     pub device_name: String,
     pub device_type: String,
+    pub verification_key: Option<String>,
 }
 
 /// Start the notification listener in the background
@@ -338,6 +302,7 @@ pub fn start_notification_listener(
 pub async fn show_pairing_notification(
     device_name: &str,
     _device_id: &str,
+    verification_key: Option<&str>,
 ) -> Result<(), Box<dyn StdError + Send + Sync>> {
     eprintln!("=== Showing Notification ===");
     eprintln!("Device: {} ({})", device_name, _device_id);
@@ -349,7 +314,13 @@ pub async fn show_pairing_notification(
     close_kdeconnect_notifications().await;
     
     let summary = format!("{} wants to pair", device_name);
-    let body = "Click to open settings and accept or reject";
+    let body = match verification_key {
+        Some(key) => format!(
+            "Verification key: {}\nCheck that it matches the key on the device, then open settings to accept or reject",
+            key
+        ),
+        None => "Click to open settings and accept or reject".to_string(),
+    };
     
     // Create the kdeconnect URL for our app
     let url = format!("kdeconnect://pair/{}", _device_id);
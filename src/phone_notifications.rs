@@ -0,0 +1,401 @@
+// src/phone_notifications.rs
+//! Notifications shown on a device, listed on its card and optionally
+//! mirrored as desktop notifications.
+//!
+//! kdeconnectd keeps an object for each notification the phone reports.
+//! Mirrored popups carry the phone's action buttons, and an inline reply
+//! field when both the phone app and the notification server support one;
+//! dismissing a popup dismisses the notification on the phone.
+//!
+//! kdeconnectd shows its own popup for each notification unless that is
+//! turned off in KDE Connect's notification settings (`kdeconnect.notifyrc`),
+//! so mirroring is off until it is turned on for a device. The devices it is
+//! on for are kept in `$XDG_CONFIG_HOME/cosmic-connect/mirrored-notifications.json`.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::PathBuf;
+
+use futures::{Stream, StreamExt};
+use zbus::zvariant::Value;
+use zbus::Connection;
+
+use crate::dbus::{self, get_connection};
+use crate::proxies::{self, DesktopNotificationsProxy, NotificationProxy, NotificationsProxy};
+
+/// `NotificationClosed` reason for a popup that timed out
+const EXPIRED: u32 = 1;
+/// `NotificationClosed` reason for a popup the user closed
+const DISMISSED_BY_USER: u32 = 2;
+/// Action key and capability of the inline reply field
+const INLINE_REPLY: &str = "inline-reply";
+/// Action key of a click on the popup's body
+const DEFAULT_ACTION: &str = "default";
+const FALLBACK_ICON: &str = "phone-symbolic";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoneNotification {
+    /// kdeconnectd's public ID, the last part of the object path
+    pub id: String,
+    /// The phone's key for the notification, which actions are sent with
+    pub key: String,
+    pub app_name: String,
+    pub title: String,
+    pub text: String,
+    pub icon_path: Option<PathBuf>,
+    /// Labels of the action buttons
+    pub actions: Vec<String>,
+    pub can_reply: bool,
+    pub dismissable: bool,
+    /// Sent without alerting, so no popup is shown for it
+    pub silent: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+    /// A new or changed notification
+    Posted(String, PhoneNotification), // device_id, notification
+    Removed(String, String), // device_id, notification id
+    Cleared(String), // device_id
+    /// A mirrored popup was clicked, to show the device's notifications
+    Opened(String), // device_id
+}
+
+/// Everything that can happen to the device's notifications or their popups
+enum Signal {
+    Changed(String),
+    Removed(String),
+    Cleared,
+    ActionInvoked(u32, String),
+    Replied(u32, String),
+    Closed(u32, u32),
+}
+
+/// The device's notifications, for an iced subscription. With `mirror`, new
+/// ones are shown as popups while the stream runs.
+///
+/// Notifications already on the phone are listed first, without popups.
+pub fn watch(device_id: String, mirror: bool) -> impl Stream<Item = NotificationEvent> {
+    async_stream::stream! {
+        let conn = match get_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                eprintln!("Can't watch notifications of {}: {}", device_id, e);
+                return;
+            }
+        };
+        let plugin: NotificationsProxy<'static> = match proxies::device_object(&conn, &device_id).await {
+            Ok(plugin) => plugin,
+            Err(e) => {
+                eprintln!("Can't watch notifications of {}: {}", device_id, e);
+                return;
+            }
+        };
+        let (Ok(posted), Ok(updated), Ok(removed), Ok(cleared)) = (
+            plugin.receive_notification_posted().await,
+            plugin.receive_notification_updated().await,
+            plugin.receive_notification_removed().await,
+            plugin.receive_all_notifications_removed().await,
+        ) else {
+            eprintln!("Can't watch notifications of {}", device_id);
+            return;
+        };
+
+        let mut mirror = Mirror::new(&conn, mirror).await;
+        let mut signals = futures::stream::select_all(vec![
+            posted.filter_map(|s| async move { s.args().ok().map(|args| Signal::Changed(args.public_id)) }).boxed(),
+            updated.filter_map(|s| async move { s.args().ok().map(|args| Signal::Changed(args.public_id)) }).boxed(),
+            removed.filter_map(|s| async move { s.args().ok().map(|args| Signal::Removed(args.public_id)) }).boxed(),
+            cleared.map(|_| Signal::Cleared).boxed(),
+        ]);
+        if let Some(desktop) = mirror.desktop.as_ref() {
+            if let (Ok(invoked), Ok(replied), Ok(closed)) = (
+                desktop.receive_action_invoked().await,
+                desktop.receive_notification_replied().await,
+                desktop.receive_notification_closed().await,
+            ) {
+                signals.push(invoked.filter_map(|s| async move { s.args().ok().map(|args| Signal::ActionInvoked(args.id, args.action_key)) }).boxed());
+                signals.push(replied.filter_map(|s| async move { s.args().ok().map(|args| Signal::Replied(args.id, args.text)) }).boxed());
+                signals.push(closed.filter_map(|s| async move { s.args().ok().map(|args| Signal::Closed(args.id, args.reason)) }).boxed());
+            }
+        }
+
+        for id in plugin.active_notifications().await.unwrap_or_default() {
+            if let Some(notification) = fetch(&conn, &device_id, &id).await {
+                yield NotificationEvent::Posted(device_id.clone(), notification);
+            }
+        }
+
+        while let Some(signal) = signals.next().await {
+            match signal {
+                Signal::Changed(id) => {
+                    if let Some(notification) = fetch(&conn, &device_id, &id).await {
+                        mirror.show(&notification).await;
+                        yield NotificationEvent::Posted(device_id.clone(), notification);
+                    }
+                }
+                Signal::Removed(id) => {
+                    mirror.close(&id).await;
+                    yield NotificationEvent::Removed(device_id.clone(), id);
+                }
+                Signal::Cleared => {
+                    mirror.close_all().await;
+                    yield NotificationEvent::Cleared(device_id.clone());
+                }
+                // Popups of other devices are left to their own streams
+                Signal::ActionInvoked(popup, action) => {
+                    let Some(notification) = mirror.take(popup) else {
+                        continue;
+                    };
+                    if action == DEFAULT_ACTION {
+                        yield NotificationEvent::Opened(device_id.clone());
+                    } else if notification.actions.contains(&action) {
+                        // Desktop-only keys such as the inline reply's never reach the phone
+                        let _ = dbus::send_notification_action(device_id.clone(), notification.key, action).await;
+                    }
+                }
+                Signal::Replied(popup, text) => {
+                    if let Some(notification) = mirror.take(popup) {
+                        let _ = dbus::reply_to_notification(device_id.clone(), notification.id, text).await;
+                    }
+                }
+                // An expired popup is kept, so updates replace it instead of
+                // posting another one
+                Signal::Closed(popup, EXPIRED) => mirror.expire(popup),
+                Signal::Closed(popup, reason) => {
+                    if let Some(notification) = mirror.take(popup) {
+                        if reason == DISMISSED_BY_USER && notification.dismissable {
+                            let _ = dbus::dismiss_notification(device_id.clone(), notification.id).await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn fetch(conn: &Connection, device_id: &str, id: &str) -> Option<PhoneNotification> {
+    let result = match proxies::notification(conn, device_id, id).await {
+        Ok(notification) => read(&notification, id).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(notification) => Some(notification),
+        Err(e) => {
+            // Removed again before it could be read
+            eprintln!("Can't read notification {} of {}: {}", id, device_id, e);
+            None
+        }
+    }
+}
+
+async fn read(notification: &NotificationProxy<'_>, id: &str) -> zbus::Result<PhoneNotification> {
+    let icon_path = if notification.has_icon().await? {
+        Some(notification.icon_path().await?)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    } else {
+        None
+    };
+
+    Ok(PhoneNotification {
+        id: id.to_string(),
+        key: notification.internal_id().await?,
+        app_name: notification.app_name().await?,
+        title: notification.title().await?,
+        text: notification.text().await?,
+        icon_path,
+        actions: notification.actions().await?,
+        can_reply: !notification.reply_id().await?.is_empty(),
+        dismissable: notification.dismissable().await?,
+        silent: notification.silent().await?,
+    })
+}
+
+/// Desktop popups for one device's notifications
+struct Mirror {
+    /// `None` when mirroring is off or no notification server is running
+    desktop: Option<DesktopNotificationsProxy<'static>>,
+    inline_reply: bool,
+    /// Popups by their ID on the notification server
+    popups: HashMap<u32, PhoneNotification>,
+    /// Popups that timed out but whose notification is still on the phone
+    expired: HashSet<u32>,
+}
+
+impl Mirror {
+    async fn new(conn: &Connection, enabled: bool) -> Self {
+        let desktop = if enabled {
+            match DesktopNotificationsProxy::new(conn).await {
+                Ok(desktop) => Some(desktop),
+                Err(e) => {
+                    eprintln!("Not mirroring notifications: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let inline_reply = match desktop.as_ref() {
+            Some(desktop) => desktop.get_capabilities().await
+                .is_ok_and(|capabilities| capabilities.iter().any(|c| c == INLINE_REPLY)),
+            None => false,
+        };
+
+        Self { desktop, inline_reply, popups: HashMap::new(), expired: HashSet::new() }
+    }
+
+    fn popup_of(&self, id: &str) -> Option<u32> {
+        self.popups.iter()
+            .find(|(_, notification)| notification.id == id)
+            .map(|(popup, _)| *popup)
+    }
+
+    /// Show a popup for the notification, or update the one shown before
+    async fn show(&mut self, notification: &PhoneNotification) {
+        let Some(desktop) = self.desktop.as_ref() else {
+            return;
+        };
+        let open = self.popup_of(&notification.id);
+        if open.is_none() && notification.silent {
+            return;
+        }
+
+        // Actions come in key, label pairs; the phone only knows the labels
+        let mut actions = vec![DEFAULT_ACTION, ""];
+        if notification.can_reply && self.inline_reply {
+            actions.extend([INLINE_REPLY, "Reply"]);
+        }
+        for action in &notification.actions {
+            actions.extend([action.as_str(), action.as_str()]);
+        }
+
+        let icon = notification.icon_path.as_deref()
+            .and_then(|path| path.to_str())
+            .unwrap_or(FALLBACK_ICON);
+        let summary = if notification.title.is_empty() { &notification.app_name } else { &notification.title };
+
+        // An update brings an expired popup back, but shouldn't alert again
+        let expired = open.is_some_and(|popup| self.expired.contains(&popup));
+        let mut hints = HashMap::new();
+        if notification.silent || expired {
+            hints.insert("suppress-sound", Value::from(true));
+        }
+
+        match desktop.notify(&notification.app_name, open.unwrap_or(0), icon, summary, &notification.text, &actions, hints, -1).await {
+            Ok(popup) => {
+                if let Some(open) = open {
+                    self.popups.remove(&open);
+                    self.expired.remove(&open);
+                }
+                self.popups.insert(popup, notification.clone());
+            }
+            Err(e) => eprintln!("✗ Failed to mirror notification: {}", e),
+        }
+    }
+
+    /// The notification behind one of this device's popups, which is done
+    /// with once the user acted on it
+    fn take(&mut self, popup: u32) -> Option<PhoneNotification> {
+        self.expired.remove(&popup);
+        self.popups.remove(&popup)
+    }
+
+    fn expire(&mut self, popup: u32) {
+        if self.popups.contains_key(&popup) {
+            self.expired.insert(popup);
+        }
+    }
+
+    async fn close(&mut self, id: &str) {
+        let Some(popup) = self.popup_of(id) else {
+            return;
+        };
+        self.popups.remove(&popup);
+        if !self.expired.remove(&popup) {
+            if let Some(desktop) = self.desktop.as_ref() {
+                let _ = desktop.close_notification(popup).await;
+            }
+        }
+    }
+
+    async fn close_all(&mut self) {
+        let expired = std::mem::take(&mut self.expired);
+        if let Some(desktop) = self.desktop.as_ref() {
+            for (popup, _) in self.popups.drain() {
+                if !expired.contains(&popup) {
+                    let _ = desktop.close_notification(popup).await;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Mirror {
+    /// The stream ends when the device disconnects, and its notifications
+    /// are gone with it
+    fn drop(&mut self) {
+        let (Some(desktop), Ok(runtime)) = (self.desktop.clone(), tokio::runtime::Handle::try_current()) else {
+            return;
+        };
+        let popups: Vec<u32> = self.popups.drain()
+            .map(|(popup, _)| popup)
+            .filter(|popup| !self.expired.contains(popup))
+            .collect();
+        if !popups.is_empty() {
+            runtime.spawn(async move {
+                for popup in popups {
+                    let _ = desktop.close_notification(popup).await;
+                }
+            });
+        }
+    }
+}
+
+fn mirrored_path() -> PathBuf {
+    let base = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            PathBuf::from(home).join(".config")
+        });
+
+    base.join("cosmic-connect").join("mirrored-notifications.json")
+}
+
+/// Load the IDs of the devices whose notifications are mirrored
+pub fn load_mirrored() -> HashSet<String> {
+    let Ok(content) = std::fs::read_to_string(mirrored_path()) else {
+        return HashSet::new();
+    };
+
+    match serde_json::from_str::<serde_json::Value>(&content) {
+        Ok(serde_json::Value::Array(ids)) => ids
+            .iter()
+            .filter_map(|id| id.as_str().map(str::to_string))
+            .collect(),
+        _ => {
+            eprintln!("Ignoring unreadable mirrored notification settings");
+            HashSet::new()
+        }
+    }
+}
+
+/// Turn mirroring on or off for a device and save the setting
+pub fn set_mirrored(mirrored: &mut HashSet<String>, device_id: &str, enabled: bool) -> io::Result<()> {
+    if enabled {
+        mirrored.insert(device_id.to_string());
+    } else {
+        mirrored.remove(device_id);
+    }
+
+    let mut ids: Vec<&String> = mirrored.iter().collect();
+    ids.sort();
+    let path = mirrored_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::Value::from(ids.into_iter().cloned().collect::<Vec<_>>()).to_string())
+}
@@ -3,6 +3,10 @@
 //!
 //! This module handles reading and writing plugin-specific configuration
 //! settings for each device, stored in ~/.config/kdeconnect/{device_id}/{plugin_name}/config
+//!
+//! Settings kdeconnectd has no key for are kept in the applet's own
+//! ~/.config/cosmic-connect/{device_id}/{plugin_name}/config instead, so
+//! kdeconnectd's files only ever hold keys it reads.
 
 use std::path::PathBuf;
 use std::fs;
@@ -742,6 +746,95 @@ impl SendNotificationsPluginConfig {
     }
 }
 
+/// Configuration for the Multimedia control receiver plugin (mpriscontrol)
+#[derive(Debug, Clone, Default)]
+pub struct MprisControlPluginConfig {
+    /// Identities of local MPRIS players hidden from this device
+    pub excluded_players: Vec<String>,
+}
+
+impl MprisControlPluginConfig {
+    /// Load configuration from file
+    pub fn load(device_id: &str) -> io::Result<Self> {
+        let config_path = Self::get_config_path(device_id);
+        
+        if !config_path.exists() {
+            eprintln!("MprisControl plugin config not found for device {}, using defaults", device_id);
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&config_path)?;
+        
+        // Parse the KDE config file format (key=value, lists are comma separated)
+        let mut config = Self::default();
+        
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                continue;
+            }
+            
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "excludedPlayers" {
+                    config.excluded_players = value
+                        .split(',')
+                        .map(|player| player.trim().to_string())
+                        .filter(|player| !player.is_empty())
+                        .collect();
+                }
+            }
+        }
+        
+        Ok(config)
+    }
+    
+    /// Save configuration to file
+    pub fn save(&self, device_id: &str) -> io::Result<()> {
+        let config_path = Self::get_config_path(device_id);
+        
+        // Ensure directory exists
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        // Write config file in KDE config format
+        let mut file = fs::File::create(&config_path)?;
+        writeln!(file, "[General]")?;
+        writeln!(file, "excludedPlayers={}", self.excluded_players.join(","))?;
+        
+        eprintln!("✓ Saved mpriscontrol plugin config for device {}", device_id);
+        eprintln!("  Path: {}", config_path.display());
+        eprintln!("  Excluded players: {:?}", self.excluded_players);
+        
+        Ok(())
+    }
+    
+    /// Whether the player with this identity is hidden from the device
+    pub fn is_excluded(&self, player: &str) -> bool {
+        self.excluded_players.iter().any(|p| p == player)
+    }
+    
+    /// Hide or expose a player, keeping the list free of duplicates
+    pub fn set_excluded(&mut self, player: &str, excluded: bool) {
+        self.excluded_players.retain(|p| p != player);
+        if excluded {
+            self.excluded_players.push(player.to_string());
+        }
+    }
+    
+    /// Get the config file path for a device's mpriscontrol plugin; the
+    /// applet's own, as kdeconnectd has no exclusion key
+    fn get_config_path(device_id: &str) -> PathBuf {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        PathBuf::from(format!("{}/.config/cosmic-connect/{}/kdeconnect_mpriscontrol/config", home, device_id))
+    }
+    
+    /// Check if a config file exists for the device
+    pub fn exists(device_id: &str) -> bool {
+        Self::get_config_path(device_id).exists()
+    }
+}
+
 /// All plugin-specific configurations
 #[derive(Debug, Clone)]
 pub struct PluginConfigs {
@@ -751,6 +844,7 @@ pub struct PluginConfigs {
     pub pausemusic: PauseMusicPluginConfig,
     pub findmyphone: FindMyPhonePluginConfig,
     pub sendnotifications: SendNotificationsPluginConfig,
+    pub mpriscontrol: MprisControlPluginConfig,
 }
 
 impl PluginConfigs {
@@ -763,6 +857,7 @@ impl PluginConfigs {
             pausemusic: PauseMusicPluginConfig::load(device_id).unwrap_or_default(),
             findmyphone: FindMyPhonePluginConfig::load(device_id).unwrap_or_default(),
             sendnotifications: SendNotificationsPluginConfig::load(device_id).unwrap_or_default(),
+            mpriscontrol: MprisControlPluginConfig::load(device_id).unwrap_or_default(),
         }
     }
     
@@ -774,6 +869,7 @@ impl PluginConfigs {
         self.pausemusic.save(device_id)?;
         self.findmyphone.save(device_id)?;
         self.sendnotifications.save(device_id)?;
+        self.mpriscontrol.save(device_id)?;
         Ok(())
     }
 }
@@ -1,4 +1,5 @@
 // src/plugins/mod.rs
 //! Plugins module for KDE Connect functionality.
 
+pub mod remote_input;
 pub mod sms;
\ No newline at end of file
@@ -0,0 +1,262 @@
+// src/plugins/remote_input/app.rs
+//! Main remote input window application logic.
+
+use cosmic::app::{Core, Task};
+use cosmic::iced::keyboard;
+use cosmic::iced::mouse::ScrollDelta;
+use cosmic::iced::{event, Alignment, Event, Length, Point, Subscription};
+use cosmic::widget;
+use cosmic::{Application, ApplicationExt, Element};
+
+use super::keys::{self, KeyPress};
+use super::messages::RemoteInputMessage;
+use crate::dbus::{self, MouseCommand};
+use crate::models::{Plugin, PluginSet};
+
+/// Pixels scrolled per wheel notch
+const SCROLL_LINE: f64 = 20.0;
+
+/// How often gathered pointer motion is sent to the device
+const POINTER_FLUSH: std::time::Duration = std::time::Duration::from_millis(16);
+
+/// The remote input window application state.
+pub struct RemoteInputWindow {
+    core: Core,
+    device_id: String,
+    device_name: String,
+    /// `None` until fetched, or when the daemon couldn't be asked
+    plugins: Option<PluginSet>,
+    /// Last pointer position over the touchpad, to turn positions into motion
+    last_pointer: Option<Point>,
+    /// Motion not sent yet, including the sub-pixel remainder
+    pending_motion: (f32, f32),
+    error: Option<String>,
+}
+
+impl Application for RemoteInputWindow {
+    type Executor = cosmic::executor::Default;
+    type Flags = (String, String);
+    type Message = RemoteInputMessage;
+    const APP_ID: &str = "io.github.M4LC0ntent.CosmicKdeConnect.RemoteInput";
+
+    fn core(&self) -> &Core {
+        &self.core
+    }
+
+    fn core_mut(&mut self) -> &mut Core {
+        &mut self.core
+    }
+
+    fn init(core: Core, flags: Self::Flags) -> (Self, Task<Self::Message>) {
+        let (device_id, device_name) = flags;
+
+        eprintln!("=== Remote Input Window Starting ===");
+        eprintln!("Device: {} ({})", device_name, device_id);
+
+        let mut app = RemoteInputWindow {
+            core,
+            device_id: device_id.clone(),
+            device_name: device_name.clone(),
+            plugins: None,
+            last_pointer: None,
+            pending_motion: (0.0, 0.0),
+            error: None,
+        };
+
+        let title = format!("Remote Input - {}", device_name);
+        let title_task = app.set_window_title(title, app.core.main_window_id().unwrap());
+
+        (
+            app,
+            Task::batch(vec![
+                title_task,
+                cosmic::task::future(async move {
+                    let plugins = match dbus::fetch_devices().await {
+                        Ok(devices) => devices.into_iter().find(|d| d.id == device_id).map(|d| d.plugins),
+                        Err(e) => {
+                            eprintln!("✗ Failed to fetch device: {:?}", e);
+                            None
+                        }
+                    };
+                    RemoteInputMessage::PluginsLoaded(plugins)
+                }),
+            ]),
+        )
+    }
+
+    fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
+        match message {
+            RemoteInputMessage::PluginsLoaded(plugins) => {
+                self.plugins = plugins;
+            }
+            RemoteInputMessage::KeyPressed(press) => {
+                if self.has_plugin(Plugin::RemoteKeyboard) {
+                    return self.send_key(press);
+                }
+            }
+            RemoteInputMessage::PointerMoved(point) => {
+                if let Some(last) = self.last_pointer {
+                    self.pending_motion.0 += point.x - last.x;
+                    self.pending_motion.1 += point.y - last.y;
+                }
+                self.last_pointer = Some(point);
+            }
+            RemoteInputMessage::PointerLeft => {
+                self.last_pointer = None;
+            }
+            RemoteInputMessage::FlushPointer => {
+                // Whole pixels go out now, the remainder with the next flush
+                let dx = self.pending_motion.0.trunc();
+                let dy = self.pending_motion.1.trunc();
+                if dx == 0.0 && dy == 0.0 {
+                    return Task::none();
+                }
+                self.pending_motion.0 -= dx;
+                self.pending_motion.1 -= dy;
+
+                let device_id = self.device_id.clone();
+                return cosmic::task::future(async move {
+                    RemoteInputMessage::Sent(dbus::move_remote_cursor(device_id, dx as i32, dy as i32).await)
+                });
+            }
+            RemoteInputMessage::Mouse(command) => {
+                return self.send_mouse(command);
+            }
+            RemoteInputMessage::Scrolled(delta) => {
+                let (dx, dy) = match delta {
+                    ScrollDelta::Lines { x, y } => (f64::from(x) * SCROLL_LINE, f64::from(y) * SCROLL_LINE),
+                    ScrollDelta::Pixels { x, y } => (f64::from(x), f64::from(y)),
+                };
+                // The wheel reports content motion, the device expects scroll direction
+                return self.send_mouse(MouseCommand::Scroll { dx: -dx, dy: -dy });
+            }
+            RemoteInputMessage::Sent(result) => {
+                self.error = result.err().map(|e| e.to_string());
+            }
+        }
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let keyboard_hint = if self.has_plugin(Plugin::RemoteKeyboard) {
+            format!("Type anywhere in this window to type on {}", self.device_name)
+        } else {
+            "Enable the Remote keypresses plugin to type on this device".to_string()
+        };
+
+        let mut content = widget::column()
+            .spacing(spacing.space_xs)
+            .push(widget::text("Keyboard").size(14).font(cosmic::font::bold()))
+            .push(widget::text(keyboard_hint).size(12))
+            .push(widget::Space::with_height(Length::Fixed(spacing.space_s as f32)))
+            .push(widget::text("Touchpad").size(14).font(cosmic::font::bold()));
+
+        if self.has_plugin(Plugin::RemoteControl) {
+            let touchpad = widget::container(
+                widget::text("Move, click and scroll here").size(12)
+            )
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .class(cosmic::theme::Container::Card);
+
+            content = content
+                .push(
+                    cosmic::iced::widget::mouse_area(touchpad)
+                        .on_move(RemoteInputMessage::PointerMoved)
+                        .on_exit(RemoteInputMessage::PointerLeft)
+                        .on_press(RemoteInputMessage::Mouse(MouseCommand::Click))
+                        .on_right_press(RemoteInputMessage::Mouse(MouseCommand::RightClick))
+                        .on_middle_press(RemoteInputMessage::Mouse(MouseCommand::MiddleClick))
+                        .on_scroll(RemoteInputMessage::Scrolled)
+                )
+                .push(
+                    widget::row()
+                        .push(
+                            widget::button::standard("Click")
+                                .on_press(RemoteInputMessage::Mouse(MouseCommand::Click))
+                        )
+                        .push(
+                            widget::button::standard("Double click")
+                                .on_press(RemoteInputMessage::Mouse(MouseCommand::DoubleClick))
+                        )
+                        .push(
+                            widget::button::standard("Right click")
+                                .on_press(RemoteInputMessage::Mouse(MouseCommand::RightClick))
+                        )
+                        .spacing(spacing.space_xs)
+                );
+        } else {
+            content = content.push(
+                widget::text("Enable the Remote control plugin to use this device's pointer").size(12)
+            );
+        }
+
+        if let Some(error) = &self.error {
+            content = content.push(
+                widget::row()
+                    .push(widget::icon::from_name("dialog-warning-symbolic").size(16))
+                    .push(widget::text(error.as_str()).size(12))
+                    .spacing(spacing.space_xs)
+                    .align_y(Alignment::Center)
+            );
+        }
+
+        widget::container(content.padding(spacing.space_m))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let keys = event::listen_with(|event, _status, _window| match event {
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, text, .. }) => {
+                keys::translate(&key, modifiers, text.as_deref()).map(RemoteInputMessage::KeyPressed)
+            }
+            _ => None,
+        });
+
+        if self.pending_motion.0.abs() >= 1.0 || self.pending_motion.1.abs() >= 1.0 {
+            Subscription::batch(vec![
+                keys,
+                cosmic::iced::time::every(POINTER_FLUSH).map(|_| RemoteInputMessage::FlushPointer),
+            ])
+        } else {
+            keys
+        }
+    }
+}
+
+// Private implementation methods
+impl RemoteInputWindow {
+    /// Whether the plugin is loaded; assumed so while that is unknown
+    fn has_plugin(&self, plugin: Plugin) -> bool {
+        self.plugins.as_ref().is_none_or(|plugins| plugins.is_loaded(plugin))
+    }
+
+    fn send_key(&self, press: KeyPress) -> Task<RemoteInputMessage> {
+        let device_id = self.device_id.clone();
+        cosmic::task::future(async move {
+            RemoteInputMessage::Sent(
+                dbus::send_key_press(device_id, press.key, press.special_key, press.shift, press.ctrl, press.alt).await
+            )
+        })
+    }
+
+    fn send_mouse(&self, command: MouseCommand) -> Task<RemoteInputMessage> {
+        let device_id = self.device_id.clone();
+        cosmic::task::future(async move {
+            RemoteInputMessage::Sent(dbus::send_mouse_command(device_id, command).await)
+        })
+    }
+}
+
+/// Runs the remote input window application.
+pub fn run(device_id: String, device_name: String) -> cosmic::iced::Result {
+    let settings = cosmic::app::Settings::default()
+        .size_limits(cosmic::iced::Limits::NONE.min_width(400.0).min_height(360.0))
+        .size(cosmic::iced::Size::new(520.0, 460.0));
+
+    cosmic::app::run::<RemoteInputWindow>(settings, (device_id, device_name))
+}
@@ -0,0 +1,109 @@
+// src/plugins/remote_input/keys.rs
+//! Translation of keyboard events into remote keyboard key presses.
+
+use cosmic::iced::keyboard::key::Named;
+use cosmic::iced::keyboard::{Key, Modifiers};
+
+/// A key press as the remote keyboard plugin sends it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPress {
+    /// Text to type; empty for special keys
+    pub key: String,
+    /// KDE Connect special key code, 0 for none
+    pub special_key: i32,
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+/// Build the key press for a keyboard event, or `None` for keys the protocol
+/// can't express (lone modifiers, media keys, ...).
+///
+/// `key` is the key without modifiers applied and `text` what it typed.
+pub fn translate(key: &Key, modifiers: Modifiers, text: Option<&str>) -> Option<KeyPress> {
+    let press = |key: String, special_key: i32, shift: bool| KeyPress {
+        key,
+        special_key,
+        shift,
+        ctrl: modifiers.control(),
+        alt: modifiers.alt(),
+    };
+
+    match key {
+        Key::Named(Named::Space) => Some(press(" ".to_string(), 0, false)),
+        Key::Named(named) => special_key(*named).map(|code| press(String::new(), code, modifiers.shift())),
+        // Shortcuts are sent as the bare key, so Ctrl+C arrives as Ctrl + "c"
+        Key::Character(c) if modifiers.control() || modifiers.alt() => {
+            Some(press(c.to_string(), 0, modifiers.shift()))
+        }
+        // Otherwise the typed text already carries Shift and the keyboard layout
+        Key::Character(c) => {
+            let text = text.filter(|t| !t.chars().any(char::is_control)).unwrap_or(c.as_str());
+            Some(press(text.to_string(), 0, false))
+        }
+        Key::Unidentified => None,
+    }
+}
+
+/// KDE Connect's code for a non-printing key
+fn special_key(named: Named) -> Option<i32> {
+    let code = match named {
+        Named::Backspace => 1,
+        Named::Tab => 2,
+        Named::ArrowLeft => 4,
+        Named::ArrowUp => 5,
+        Named::ArrowRight => 6,
+        Named::ArrowDown => 7,
+        Named::PageUp => 8,
+        Named::PageDown => 9,
+        Named::Home => 10,
+        Named::End => 11,
+        Named::Enter => 12,
+        Named::Delete => 13,
+        Named::Escape => 14,
+        Named::PrintScreen => 15,
+        Named::ScrollLock => 16,
+        Named::F1 => 21,
+        Named::F2 => 22,
+        Named::F3 => 23,
+        Named::F4 => 24,
+        Named::F5 => 25,
+        Named::F6 => 26,
+        Named::F7 => 27,
+        Named::F8 => 28,
+        Named::F9 => 29,
+        Named::F10 => 30,
+        Named::F11 => 31,
+        Named::F12 => 32,
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_special_keys() {
+        let press = translate(&Key::Named(Named::Enter), Modifiers::SHIFT, None).unwrap();
+        assert_eq!(press.key, "");
+        assert_eq!(press.special_key, 12);
+        assert!(press.shift);
+
+        assert_eq!(translate(&Key::Named(Named::Shift), Modifiers::SHIFT, None), None);
+    }
+
+    #[test]
+    fn test_characters() {
+        let key = Key::Character("a".into());
+
+        let typed = translate(&key, Modifiers::SHIFT, Some("A")).unwrap();
+        assert_eq!(typed.key, "A");
+        assert!(!typed.shift);
+
+        let shortcut = translate(&key, Modifiers::CTRL, Some("\u{1}")).unwrap();
+        assert_eq!(shortcut.key, "a");
+        assert!(shortcut.ctrl);
+    }
+}
@@ -0,0 +1,30 @@
+// src/plugins/remote_input/messages.rs
+//! Message types for the remote input window application.
+
+use cosmic::iced::mouse::ScrollDelta;
+use cosmic::iced::Point;
+
+use super::keys::KeyPress;
+use crate::dbus::{KdeConnectError, MouseCommand};
+use crate::models::PluginSet;
+
+/// All possible messages that the remote input window can receive and process.
+#[derive(Debug, Clone)]
+pub enum RemoteInputMessage {
+    /// Loaded plugins of the device, once fetched
+    PluginsLoaded(Option<PluginSet>),
+
+    // Keyboard
+    KeyPressed(KeyPress),
+
+    // Touchpad
+    PointerMoved(Point),
+    PointerLeft,
+    /// Send the motion gathered since the last flush
+    FlushPointer,
+    Mouse(MouseCommand),
+    Scrolled(ScrollDelta),
+
+    // Action feedback
+    Sent(Result<(), KdeConnectError>),
+}
@@ -0,0 +1,15 @@
+// src/plugins/remote_input/mod.rs
+//! Remote input plugin module for KDE Connect.
+//!
+//! A small window that types on the device through the remote keyboard plugin
+//! and drives its pointer through mousepad requests.
+
+// Suppress warnings for code used by binaries (not directly visible to lib crate)
+#![allow(dead_code)]
+
+mod app;
+mod keys;
+mod messages;
+
+// Re-export the run function for the binary
+pub use app::run;
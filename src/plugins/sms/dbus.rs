@@ -3,17 +3,14 @@
 
 use futures::StreamExt;
 use std::collections::HashMap;
-use zbus::{Connection, MatchRule};
+use zbus::zvariant::Value;
+use zbus::Connection;
 
+use crate::proxies::{self, ContactsProxy, ConversationsProxy, DeviceProxy};
 use super::models::{ContactsMap, Conversation, Message, SignalEvent};
 use super::messages::SmsMessage;
 use super::utils::{now_millis, parse_vcard};
 
-const KDECONNECT_SERVICE: &str = "org.kde.kdeconnect";
-const CONVERSATIONS_INTERFACE: &str = "org.kde.kdeconnect.device.conversations";
-const DEVICE_INTERFACE: &str = "org.kde.kdeconnect.device";
-const CONTACTS_INTERFACE: &str = "org.kde.kdeconnect.device.contacts";
-
 /// Fetches all conversations from the device.
 pub async fn fetch_conversations(device_id: String) -> Vec<Conversation> {
     let mut conversations = Vec::new();
@@ -28,37 +25,27 @@ pub async fn fetch_conversations(device_id: String) -> Vec<Conversation> {
         }
     };
 
-    let path = format!("/modules/kdeconnect/devices/{}", device_id);
+    let proxy = match proxies::device_object::<ConversationsProxy>(&conn, &device_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("✗ D-Bus failed: {:?}", e);
+            return conversations;
+        }
+    };
     
     // Request all conversation threads
-    let _ = conn.call_method(
-        Some(KDECONNECT_SERVICE),
-        path.as_str(),
-        Some(CONVERSATIONS_INTERFACE),
-        "requestAllConversationThreads",
-        &()
-    ).await;
+    let _ = proxy.request_all_conversation_threads().await;
     
     tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
     
     // Get active conversations
-    match conn.call_method(
-        Some(KDECONNECT_SERVICE),
-        path.as_str(),
-        Some(CONVERSATIONS_INTERFACE),
-        "activeConversations",
-        &()
-    ).await {
-        Ok(reply) => {
-            let body = reply.body();
+    match proxy.active_conversations().await {
+        Ok(conv_variants) => {
+            eprintln!("✓ Found {} conversations", conv_variants.len());
             
-            if let Ok(conv_variants) = body.deserialize::<Vec<zbus::zvariant::Value>>() {
-                eprintln!("✓ Found {} conversations", conv_variants.len());
-                
-                for variant in conv_variants {
-                    if let Some(conv) = parse_conversation_variant(&variant) {
-                        conversations.push(conv);
-                    }
+            for variant in conv_variants {
+                if let Some(conv) = parse_conversation_variant(&variant) {
+                    conversations.push(conv);
                 }
             }
         }
@@ -126,15 +113,12 @@ pub async fn request_conversation_messages(device_id: String, thread_id: String)
         }
     };
 
-    let path = format!("/modules/kdeconnect/devices/{}", device_id);
+    let result = match proxies::device_object::<ConversationsProxy>(&conn, &device_id).await {
+        Ok(proxy) => proxy.request_conversation(thread_id_i64, 0, 50).await,
+        Err(e) => Err(e),
+    };
     
-    match conn.call_method(
-        Some(KDECONNECT_SERVICE),
-        path.as_str(),
-        Some(CONVERSATIONS_INTERFACE),
-        "requestConversation",
-        &(thread_id_i64, 0i32, 50i32)
-    ).await {
+    match result {
         Ok(_) => {
             eprintln!("✓ Requested messages for thread {}", thread_id_i64);
             eprintln!("  Messages will arrive via conversationUpdated signals");
@@ -210,19 +194,17 @@ async fn send_via_dbus(device_id: &str, phone: &str, message: &str, log: &mut St
     };
 
     log.push_str("✓ D-Bus connection established\n");
-    let sms_path = format!("/modules/kdeconnect/devices/{}/sms", device_id);
-    
-    let addresses = vec![phone];
-    let attachments: Vec<String> = vec![];
-    let sub_ids: Vec<i64> = vec![];
-    
-    match conn.call_method(
-        Some(KDECONNECT_SERVICE),
-        sms_path.as_str(),
-        Some(CONVERSATIONS_INTERFACE),
-        "sendWithoutConversation",
-        &(addresses, message, attachments, sub_ids)
-    ).await {
+    
+    // Each address is a ConversationAddress, which kdeconnectd marshals as (s)
+    let addresses = vec![Value::from((phone.to_string(),))];
+    let attachments: Vec<Value> = vec![];
+    
+    let result = match proxies::device_object::<ConversationsProxy>(&conn, device_id).await {
+        Ok(proxy) => proxy.send_without_conversation(&addresses, message, &attachments).await,
+        Err(e) => Err(e),
+    };
+    
+    match result {
         Ok(_) => {
             log.push_str("✓ SMS sent successfully via D-Bus!\n");
             eprintln!("✓ SMS sent successfully via D-Bus!");
@@ -251,10 +233,8 @@ pub async fn fetch_contacts(device_id: String) -> ContactsMap {
         }
     };
 
-    let device_path = format!("/modules/kdeconnect/devices/{}", device_id);
-    
     // Check if contacts plugin is enabled
-    if !check_contacts_plugin(&conn, &device_path).await {
+    if !check_contacts_plugin(&conn, &device_id).await {
         return contacts;
     }
 
@@ -280,18 +260,16 @@ pub async fn fetch_contacts(device_id: String) -> ContactsMap {
     contacts
 }
 
-async fn check_contacts_plugin(conn: &Connection, device_path: &str) -> bool {
+async fn check_contacts_plugin(conn: &Connection, device_id: &str) -> bool {
     eprintln!("Checking if contacts plugin is enabled...");
     
-    match conn.call_method(
-        Some(KDECONNECT_SERVICE),
-        device_path,
-        Some(DEVICE_INTERFACE),
-        "hasPlugin",
-        &("kdeconnect_contacts",)
-    ).await {
-        Ok(reply) => {
-            let has_plugin = reply.body().deserialize::<bool>().unwrap_or(false);
+    let result = match proxies::device_object::<DeviceProxy>(conn, device_id).await {
+        Ok(device) => device.has_plugin("kdeconnect_contacts").await,
+        Err(e) => Err(e),
+    };
+    
+    match result {
+        Ok(has_plugin) => {
             eprintln!("Contacts plugin enabled: {}", has_plugin);
             if !has_plugin {
                 eprintln!("✗ Contacts plugin is not enabled on this device!");
@@ -307,16 +285,13 @@ async fn check_contacts_plugin(conn: &Connection, device_path: &str) -> bool {
 }
 
 async fn trigger_contacts_sync(conn: &Connection, device_id: &str) {
-    let path = format!("/modules/kdeconnect/devices/{}/contacts", device_id);
-    
     eprintln!("Calling synchronizeRemoteWithLocal...");
-    match conn.call_method(
-        Some(KDECONNECT_SERVICE),
-        path.as_str(),
-        Some(CONTACTS_INTERFACE),
-        "synchronizeRemoteWithLocal",
-        &()
-    ).await {
+    let result = match proxies::device_object::<ContactsProxy>(conn, device_id).await {
+        Ok(contacts) => contacts.synchronize_remote_with_local().await,
+        Err(e) => Err(e),
+    };
+    
+    match result {
         Ok(_) => {
             eprintln!("✓ Sync triggered, waiting for contacts...");
             tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
@@ -441,19 +416,15 @@ pub fn listen_for_sms_signals_stream(device_id: String) -> impl futures::Stream<
             }
         };
 
-        let conversations_rule = match MatchRule::builder()
-            .msg_type(zbus::message::Type::Signal)
-            .sender(KDECONNECT_SERVICE)
-            .and_then(|b| b.interface(CONVERSATIONS_INTERFACE))
-        {
-            Ok(builder) => builder.build(),
+        let proxy = match proxies::device_object::<ConversationsProxy>(&conn, &device_id).await {
+            Ok(p) => p,
             Err(e) => {
-                eprintln!("✗ Failed to build match rule: {:?}", e);
+                eprintln!("✗ Failed to create conversations proxy: {:?}", e);
                 return;
             }
         };
         
-        let mut stream = match zbus::MessageStream::for_match_rule(conversations_rule, &conn, None).await {
+        let mut stream = match proxy.receive_conversation_updated().await {
             Ok(s) => s,
             Err(e) => {
                 eprintln!("✗ Failed to create signal stream: {:?}", e);
@@ -461,34 +432,23 @@ pub fn listen_for_sms_signals_stream(device_id: String) -> impl futures::Stream<
             }
         };
         
-        eprintln!("✓ Signal stream created, listening for all signals...");
+        eprintln!("✓ Signal stream created, listening for conversationUpdated...");
         
-        while let Some(msg_result) = stream.next().await {
-            let Ok(msg) = msg_result else {
+        while let Some(signal) = stream.next().await {
+            let Ok(args) = signal.args() else {
                 continue;
             };
             
-            let header = msg.header();
-            let path = header.path().map(|p| p.as_str()).unwrap_or("");
-            
-            if !path.contains(&device_id) {
-                continue;
-            }
-            
-            let member = header.member().map(|m| m.as_str()).unwrap_or("unknown");
-            
-            if member == "conversationUpdated" {
-                if let Some(message) = parse_conversation_updated_signal(&msg) {
-                    eprintln!("📨 Message #{}: type={}, {}", 
-                        message.thread_id, 
-                        message.type_, 
-                        message.body.chars().take(40).collect::<String>()
-                    );
-                    
-                    if tx.send(SmsMessage::SignalReceived(SignalEvent::MessageReceived(message))).is_err() {
-                        eprintln!("⚠️  Receiver dropped, stopping signal listener");
-                        break;
-                    }
+            if let Some(message) = parse_conversation_updated_signal(&args.message) {
+                eprintln!("📨 Message #{}: type={}, {}", 
+                    message.thread_id, 
+                    message.type_, 
+                    message.body.chars().take(40).collect::<String>()
+                );
+                
+                if tx.send(SmsMessage::SignalReceived(SignalEvent::MessageReceived(message))).is_err() {
+                    eprintln!("⚠️  Receiver dropped, stopping signal listener");
+                    break;
                 }
             }
         }
@@ -499,9 +459,8 @@ pub fn listen_for_sms_signals_stream(device_id: String) -> impl futures::Stream<
     tokio_stream::wrappers::UnboundedReceiverStream::new(rx)
 }
 
-fn parse_conversation_updated_signal(msg: &zbus::Message) -> Option<Message> {
-    let body = msg.body();
-    let zbus::zvariant::Value::Structure(fields) = body.deserialize::<zbus::zvariant::Value>().ok()? else {
+fn parse_conversation_updated_signal(variant: &Value) -> Option<Message> {
+    let Value::Structure(fields) = variant else {
         return None;
     };
     
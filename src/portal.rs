@@ -1,5 +1,17 @@
 // src/portal.rs
-// Optimized zenity-only file picker (portal backend broken)
+// File dialogs over the xdg-desktop-portal FileChooser, and opening files
+// through OpenURI; zenity and xdg-open are only used when no portal is running
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use futures::StreamExt;
+use zbus::zvariant::{Fd, OwnedObjectPath, OwnedValue, Value};
+use zbus::Connection;
 
 /// File filter for the portal file picker
 #[derive(Debug, Clone)]
@@ -15,20 +27,96 @@ impl FileFilter {
             patterns: Vec::new(),
         }
     }
-
+    
     pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
         self.patterns.push(pattern.into());
         self
     }
-
+    
     pub fn patterns(mut self, patterns: Vec<String>) -> Self {
         self.patterns = patterns;
         self
     }
+    
+    /// The portal's `(sa(us))` form: globs are type 0, MIME types type 1
+    fn to_portal(&self) -> (String, Vec<(u32, String)>) {
+        let patterns = self.patterns.iter()
+            .map(|pattern| {
+                let kind = if pattern.contains('/') { 1 } else { 0 };
+                (kind, pattern.clone())
+            })
+            .collect();
+        (self.name.clone(), patterns)
+    }
 }
 
-// Skip portal - GTK backend can't create windows
-const USE_ZENITY_DIRECTLY: bool = true;
+type Options = HashMap<&'static str, Value<'static>>;
+type Results = HashMap<String, OwnedValue>;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.FileChooser",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop"
+)]
+trait FileChooser {
+    fn open_file(
+        &self,
+        parent_window: &str,
+        title: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+    
+    fn save_file(
+        &self,
+        parent_window: &str,
+        title: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+    
+    /// Portal properties are lowercase; zbus would ask for "Version" otherwise,
+    /// which every portal rejects
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.OpenURI",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop"
+)]
+trait OpenUri {
+    fn open_file(
+        &self,
+        parent_window: &str,
+        fd: Fd<'_>,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+    
+    fn open_directory(
+        &self,
+        parent_window: &str,
+        fd: Fd<'_>,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+    
+    /// Lowercase, like the FileChooser's
+    #[zbus(property, name = "version")]
+    fn version(&self) -> zbus::Result<u32>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.portal.Request",
+    default_service = "org.freedesktop.portal.Desktop"
+)]
+trait Request {
+    #[zbus(signal)]
+    fn response(&self, response: u32, results: Results) -> zbus::Result<()>;
+}
+
+/// FileChooser version that added the `directory` option
+const DIRECTORY_VERSION: u32 = 3;
+/// OpenURI version that added the `ask` option and OpenDirectory
+const ASK_VERSION: u32 = 3;
 
 /// Open file picker dialog for selecting files
 pub async fn pick_files(
@@ -38,24 +126,254 @@ pub async fn pick_files(
 ) -> Vec<String> {
     let title_str = title.into();
     
-    if USE_ZENITY_DIRECTLY {
-        return pick_files_zenity(title_str, multiple, filters).await;
-    }
+    let mut options = Options::new();
+    options.insert("multiple", multiple.into());
+    add_filters(&mut options, filters.as_deref());
     
-    // Portal code would go here
-    Vec::new()
+    match choose_file(Dialog::Open, &title_str, options, 0).await {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("File chooser portal unavailable ({}), using zenity", e);
+            pick_files_zenity(title_str, multiple, filters).await
+        }
+    }
 }
 
 /// Open folder picker dialog for selecting a directory
 pub async fn pick_folder(title: impl Into<String>) -> Option<String> {
     let title_str = title.into();
     
-    if USE_ZENITY_DIRECTLY {
-        return pick_folder_zenity(title_str).await;
+    let mut options = Options::new();
+    options.insert("directory", true.into());
+    
+    match choose_file(Dialog::Open, &title_str, options, DIRECTORY_VERSION).await {
+        Ok(folders) => folders.into_iter().next(),
+        Err(e) => {
+            eprintln!("File chooser portal unavailable ({}), using zenity", e);
+            pick_folder_zenity(title_str).await
+        }
     }
+}
+
+/// Open save dialog, starting at `current_folder` with `suggested_name` filled in
+pub async fn save_file(
+    title: impl Into<String>,
+    suggested_name: impl Into<String>,
+    current_folder: Option<String>,
+    filters: Option<Vec<FileFilter>>,
+) -> Option<String> {
+    let title_str = title.into();
+    let name = suggested_name.into();
     
-    // Portal code would go here
-    None
+    let mut options = Options::new();
+    options.insert("current_name", name.clone().into());
+    if let Some(folder) = current_folder.as_ref() {
+        // A NUL-terminated byte string, not a URI
+        let mut bytes = folder.clone().into_bytes();
+        bytes.push(0);
+        options.insert("current_folder", bytes.into());
+    }
+    add_filters(&mut options, filters.as_deref());
+    
+    match choose_file(Dialog::Save, &title_str, options, 0).await {
+        Ok(files) => files.into_iter().next(),
+        Err(e) => {
+            eprintln!("File chooser portal unavailable ({}), using zenity", e);
+            save_file_zenity(title_str, name, current_folder, filters).await
+        }
+    }
+}
+
+/// Open a file or folder in its default application, or let the user pick
+/// the application when `ask` is set
+///
+/// Errors mean the path couldn't be opened at all, by the portal or xdg-open.
+pub async fn open_path(path: impl AsRef<Path>, ask: bool) -> io::Result<()> {
+    let path = path.as_ref();
+    let file = tokio::fs::File::open(path).await?.into_std().await;
+    
+    let result = open_uri_request(file, if ask { ASK_VERSION } else { 0 }, |open_uri, fd, mut options| async move {
+        options.insert("ask", ask.into());
+        request(options, |options| async move { open_uri.open_file("", fd, options).await }).await
+    }).await;
+    
+    if let Err(e) = result {
+        eprintln!("OpenURI portal unavailable ({}), using xdg-open", e);
+        xdg_open(path)?;
+    }
+    Ok(())
+}
+
+/// Show the folder containing a file in the file manager
+pub async fn reveal(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let file = tokio::fs::File::open(path).await?.into_std().await;
+    
+    let result = open_uri_request(file, ASK_VERSION, |open_uri, fd, options| async move {
+        request(options, |options| async move { open_uri.open_directory("", fd, options).await }).await
+    }).await;
+    
+    if let Err(e) = result {
+        eprintln!("OpenURI portal unavailable ({}), using xdg-open", e);
+        xdg_open(path.parent().unwrap_or(path))?;
+    }
+    Ok(())
+}
+
+fn add_filters(options: &mut Options, filters: Option<&[FileFilter]>) {
+    let Some(filter_list) = filters.filter(|list| !list.is_empty()) else {
+        return;
+    };
+    
+    let portal_filters: Vec<_> = filter_list.iter().map(FileFilter::to_portal).collect();
+    options.insert("current_filter", portal_filters[0].clone().into());
+    options.insert("filters", portal_filters.into());
+}
+
+enum Dialog {
+    Open,
+    Save,
+}
+
+/// Show one of the portal's file dialogs and wait for the answer.
+///
+/// Errors mean the portal couldn't show the dialog (not running, or older
+/// than `min_version`); a cancelled or failed dialog gives no paths.
+async fn choose_file(
+    dialog: Dialog,
+    title: &str,
+    options: Options,
+    min_version: u32,
+) -> zbus::Result<Vec<String>> {
+    let conn = crate::dbus::get_connection().await?;
+    let chooser = FileChooserProxy::new(&conn).await?;
+    
+    let version = chooser.version().await?;
+    if version < min_version {
+        return Err(zbus::Error::Failure(format!("FileChooser version {} is too old", version)));
+    }
+    
+    let results = request(options, |options| async {
+        match dialog {
+            Dialog::Open => chooser.open_file("", title, options).await,
+            Dialog::Save => chooser.save_file("", title, options).await,
+        }
+    }).await?;
+    
+    let uris = results
+        .and_then(|results| results.get("uris").and_then(|uris| uris.try_clone().ok()))
+        .and_then(|uris| Vec::<String>::try_from(uris).ok())
+        .unwrap_or_default();
+    
+    Ok(uris.iter().map(|uri| uri_to_path(uri)).collect())
+}
+
+/// Hand an open file to an OpenURI method
+async fn open_uri_request<F, Fut>(file: File, min_version: u32, call: F) -> zbus::Result<()>
+where
+    F: FnOnce(OpenUriProxy<'static>, Fd<'static>, Options) -> Fut,
+    Fut: Future<Output = zbus::Result<Option<Results>>>,
+{
+    let conn = crate::dbus::get_connection().await?;
+    let open_uri = OpenUriProxy::new(&conn).await?;
+    
+    let version = open_uri.version().await?;
+    if version < min_version {
+        return Err(zbus::Error::Failure(format!("OpenURI version {} is too old", version)));
+    }
+    
+    let fd = Fd::from(std::os::fd::OwnedFd::from(file));
+    
+    call(open_uri, fd, Options::new()).await.map(|_| ())
+}
+
+/// Make a portal request and wait for its Response signal.
+///
+/// `call` makes the request with the given options; errors are its own or
+/// the bus's. A cancelled or failed request gives `None`.
+async fn request<F, Fut>(mut options: Options, call: F) -> zbus::Result<Option<Results>>
+where
+    F: FnOnce(Options) -> Fut,
+    Fut: Future<Output = zbus::Result<OwnedObjectPath>>,
+{
+    let conn = crate::dbus::get_connection().await?;
+    
+    // Listen on the request object before asking, so a quick answer isn't missed
+    let token = handle_token();
+    let expected_path = request_path(&conn, &token)?;
+    let mut responses = receive_response(&conn, expected_path.clone()).await?;
+    
+    options.insert("handle_token", token.into());
+    let handle = call(options).await?;
+    
+    // Portals before 0.9 don't derive the path from the token
+    if handle != expected_path {
+        responses = receive_response(&conn, handle).await?;
+    }
+    
+    let Some(signal) = responses.next().await else {
+        eprintln!("✗ Portal closed the request without answering");
+        return Ok(None);
+    };
+    let args = match signal.args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("✗ Unreadable portal response: {}", e);
+            return Ok(None);
+        }
+    };
+    
+    match args.response {
+        0 => Ok(Some(args.results)),
+        1 => {
+            eprintln!("Portal request cancelled");
+            Ok(None)
+        }
+        _ => {
+            eprintln!("✗ Portal request failed");
+            Ok(None)
+        }
+    }
+}
+
+async fn receive_response(conn: &Connection, path: OwnedObjectPath) -> zbus::Result<ResponseStream> {
+    RequestProxy::builder(conn)
+        .path(path)?
+        .build()
+        .await?
+        .receive_response()
+        .await
+}
+
+/// A token unique to this process for each request
+fn handle_token() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    format!("cosmic_connect_{}_{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// The request object the portal creates for `token`
+fn request_path(conn: &Connection, token: &str) -> zbus::Result<OwnedObjectPath> {
+    let sender = conn.unique_name()
+        .ok_or_else(|| zbus::Error::Failure("No unique bus name".to_string()))?
+        .trim_start_matches(':')
+        .replace('.', "_");
+    
+    OwnedObjectPath::try_from(format!("/org/freedesktop/portal/desktop/request/{}/{}", sender, token))
+        .map_err(zbus::Error::from)
+}
+
+/// Local path of a `file://` URI; other URIs are kept as they are
+fn uri_to_path(uri: &str) -> String {
+    match uri.strip_prefix("file://") {
+        Some(path) => urlencoding::decode(path)
+            .map(|p| p.into_owned())
+            .unwrap_or_else(|_| path.to_string()),
+        None => uri.to_string(),
+    }
+}
+
+fn xdg_open(path: &Path) -> io::Result<()> {
+    std::process::Command::new("xdg-open").arg(path).spawn().map(|_| ())
 }
 
 /// Pick files using zenity
@@ -133,4 +451,45 @@ async fn pick_folder_zenity(title: String) -> Option<String> {
     }
     
     None
-}
\ No newline at end of file
+}
+
+/// Save file using zenity
+async fn save_file_zenity(
+    title: String,
+    suggested_name: String,
+    current_folder: Option<String>,
+    filters: Option<Vec<FileFilter>>,
+) -> Option<String> {
+    let suggested_path = match current_folder {
+        Some(folder) => Path::new(&folder).join(&suggested_name),
+        None => suggested_name.into(),
+    };
+    
+    let mut args = vec![
+        "--file-selection".to_string(),
+        "--save".to_string(),
+        "--confirm-overwrite".to_string(),
+        format!("--title={}", title),
+        format!("--filename={}", suggested_path.display()),
+    ];
+    
+    if let Some(filter_list) = filters {
+        for filter in filter_list {
+            if !filter.patterns.is_empty() {
+                args.push(format!("--file-filter={} | {}", filter.name, filter.patterns.join(" ")));
+            }
+        }
+    }
+    
+    match tokio::process::Command::new("zenity")
+        .args(&args)
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => {
+            let file = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!file.is_empty()).then_some(file)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,824 @@
+// src/proxies.rs
+//! Typed D-Bus proxies for the KDE Connect daemon and its device plugins.
+//!
+//! kdeconnectd exports camelCase member names, so every member carries an
+//! explicit `name` attribute. Each interface sits in its own submodule because
+//! the generated signal types are named after the D-Bus member, and several
+//! plugins share names such as `refreshed`.
+//!
+//! Per-device objects are created through [`device_object`], which fills in
+//! the object path for a device ID.
+
+use zbus::proxy::CacheProperties;
+use zbus::Connection;
+
+pub use daemon::DaemonProxy;
+pub use device::DeviceProxy;
+pub use battery::BatteryProxy;
+pub use connectivity_report::ConnectivityReportProxy;
+pub use ping::PingProxy;
+pub use findmyphone::FindMyPhoneProxy;
+pub use lockdevice::LockDeviceProxy;
+pub use share::ShareProxy;
+pub use clipboard::ClipboardProxy;
+pub use sftp::SftpProxy;
+pub use notifications::NotificationsProxy;
+pub use notification::NotificationProxy;
+pub use mprisremote::MprisRemoteProxy;
+pub use conversations::ConversationsProxy;
+pub use contacts::ContactsProxy;
+pub use remotekeyboard::RemoteKeyboardProxy;
+pub use remotecontrol::RemoteControlProxy;
+pub use virtualmonitor::VirtualMonitorProxy;
+pub use media_player::MediaPlayer2Proxy;
+pub use desktop_notifications::DesktopNotificationsProxy;
+
+/// Well-known bus name of the KDE Connect daemon.
+pub const SERVICE: &str = "org.kde.kdeconnect";
+
+/// Object path of the daemon itself.
+pub const DAEMON_PATH: &str = "/modules/kdeconnect";
+
+/// Object path of a device.
+pub fn device_path(device_id: &str) -> String {
+    format!("{}/devices/{}", DAEMON_PATH, device_id)
+}
+
+/// The daemon object at `/modules/kdeconnect`.
+pub mod daemon {
+    use std::collections::HashMap;
+
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.daemon",
+        default_service = "org.kde.kdeconnect",
+        default_path = "/modules/kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Daemon {
+        /// IDs of known devices, optionally filtered to reachable and/or paired ones.
+        #[zbus(name = "devices")]
+        fn devices(&self, only_reachable: bool, only_paired: bool) -> zbus::Result<Vec<String>>;
+
+        /// Map of device ID to device name.
+        #[zbus(name = "deviceNames")]
+        fn device_names(&self, only_reachable: bool, only_paired: bool) -> zbus::Result<HashMap<String, String>>;
+
+        /// ID this computer announces itself with.
+        #[zbus(name = "selfId")]
+        fn self_id(&self) -> zbus::Result<String>;
+
+        /// Name this computer announces itself with.
+        #[zbus(name = "announcedName")]
+        fn announced_name(&self) -> zbus::Result<String>;
+
+        /// Re-announce on all link providers.
+        #[zbus(name = "forceOnNetworkChange")]
+        fn force_on_network_change(&self) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "deviceAdded")]
+        fn device_added(&self, id: String) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "deviceRemoved")]
+        fn device_removed(&self, id: String) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "deviceVisibilityChanged")]
+        fn device_visibility_changed(&self, id: String, is_visible: bool) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "deviceListChanged")]
+        fn device_list_changed(&self) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "pairingRequestsChanged")]
+        fn pairing_requests_changed(&self) -> zbus::Result<()>;
+    }
+}
+
+/// A device at `/modules/kdeconnect/devices/<id>`.
+pub mod device {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Device {
+        // Changes arrive through `nameChanged`, not `PropertiesChanged`.
+        #[zbus(property(emits_changed_signal = "false"), name = "name")]
+        fn name(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "type")]
+        fn device_type(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "iconName")]
+        fn icon_name(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "isReachable")]
+        fn is_reachable(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "isPaired")]
+        fn is_paired(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "isPairRequested")]
+        fn is_pair_requested(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "isPairRequestedByPeer")]
+        fn is_pair_requested_by_peer(&self) -> zbus::Result<bool>;
+
+        /// 0 = not paired, 1 = requested, 2 = requested by peer, 3 = paired.
+        #[zbus(property(emits_changed_signal = "false"), name = "pairState")]
+        fn pair_state(&self) -> zbus::Result<i32>;
+
+        /// Short code derived from both devices' certificates, shown on both
+        /// ends while pairing. Empty outside of a pairing attempt.
+        #[zbus(property(emits_changed_signal = "false"), name = "verificationKey")]
+        fn verification_key(&self) -> zbus::Result<String>;
+
+        /// Plugin IDs the peer announced support for.
+        #[zbus(property, name = "supportedPlugins")]
+        fn supported_plugins(&self) -> zbus::Result<Vec<String>>;
+
+        #[zbus(name = "requestPairing")]
+        fn request_pairing(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "unpair")]
+        fn unpair(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "acceptPairing")]
+        fn accept_pairing(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "rejectPairing")]
+        fn reject_pairing(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "reloadPlugins")]
+        fn reload_plugins(&self) -> zbus::Result<()>;
+
+        /// Whether the plugin is currently loaded for this device.
+        #[zbus(name = "hasPlugin")]
+        fn has_plugin(&self, name: &str) -> zbus::Result<bool>;
+
+        /// Whether the plugin is enabled in the local configuration.
+        #[zbus(name = "isPluginEnabled")]
+        fn is_plugin_enabled(&self, name: &str) -> zbus::Result<bool>;
+
+        #[zbus(name = "setPluginEnabled")]
+        fn set_plugin_enabled(&self, name: &str, enabled: bool) -> zbus::Result<()>;
+
+        #[zbus(name = "loadedPlugins")]
+        fn loaded_plugins(&self) -> zbus::Result<Vec<String>>;
+
+        #[zbus(name = "encryptionInfo")]
+        fn encryption_info(&self) -> zbus::Result<String>;
+
+        #[zbus(signal, name = "reachableChanged")]
+        fn reachable_changed(&self, reachable: bool) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "nameChanged")]
+        fn name_changed(&self, name: String) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "typeChanged")]
+        fn type_changed(&self, device_type: String) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "pluginsChanged")]
+        fn plugins_changed(&self) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "pairStateChanged")]
+        fn pair_state_changed(&self, pair_state: i32) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "pairingFailed")]
+        fn pairing_failed(&self, error: String) -> zbus::Result<()>;
+    }
+}
+
+pub mod battery {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.battery",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Battery {
+        #[zbus(property, name = "charge")]
+        fn charge(&self) -> zbus::Result<i32>;
+
+        #[zbus(property, name = "isCharging")]
+        fn is_charging(&self) -> zbus::Result<bool>;
+
+        #[zbus(signal, name = "refreshed")]
+        fn refreshed(&self, is_charging: bool, charge: i32) -> zbus::Result<()>;
+    }
+}
+
+pub mod connectivity_report {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.connectivity_report",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait ConnectivityReport {
+        #[zbus(property, name = "cellularNetworkType")]
+        fn cellular_network_type(&self) -> zbus::Result<String>;
+
+        /// 0-4 bars, or -1 when there is no signal.
+        #[zbus(property, name = "cellularNetworkStrength")]
+        fn cellular_network_strength(&self) -> zbus::Result<i32>;
+
+        #[zbus(signal, name = "refreshed")]
+        fn refreshed(&self, network_type: String, strength: i32) -> zbus::Result<()>;
+    }
+}
+
+pub mod ping {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.ping",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Ping {
+        #[zbus(name = "sendPing")]
+        fn send_ping(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "sendPing")]
+        fn send_ping_with_message(&self, message: &str) -> zbus::Result<()>;
+    }
+}
+
+pub mod findmyphone {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.findmyphone",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait FindMyPhone {
+        #[zbus(name = "ring")]
+        fn ring(&self) -> zbus::Result<()>;
+    }
+}
+
+pub mod lockdevice {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.lockdevice",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait LockDevice {
+        #[zbus(property, name = "isLocked")]
+        fn is_locked(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "isLocked")]
+        fn set_is_locked(&self, locked: bool) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "lockedChanged")]
+        fn locked_changed(&self, locked: bool) -> zbus::Result<()>;
+    }
+}
+
+pub mod share {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.share",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Share {
+        #[zbus(name = "shareUrl")]
+        fn share_url(&self, url: &str) -> zbus::Result<()>;
+
+        #[zbus(name = "shareUrls")]
+        fn share_urls(&self, urls: &[&str]) -> zbus::Result<()>;
+
+        #[zbus(name = "shareText")]
+        fn share_text(&self, text: &str) -> zbus::Result<()>;
+
+        /// Share a file and ask the peer to open it once received.
+        #[zbus(name = "openFile")]
+        fn open_file(&self, url: &str) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "shareReceived")]
+        fn share_received(&self, url: String) -> zbus::Result<()>;
+    }
+}
+
+pub mod clipboard {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.clipboard",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Clipboard {
+        /// Send the daemon's current view of the local clipboard.
+        #[zbus(name = "sendClipboard")]
+        fn send_clipboard(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "sendClipboard")]
+        fn send_clipboard_content(&self, content: &str) -> zbus::Result<()>;
+
+        #[zbus(property, name = "isAutoShareDisabled")]
+        fn is_auto_share_disabled(&self) -> zbus::Result<bool>;
+
+        #[zbus(signal, name = "autoShareDisabledChanged")]
+        fn auto_share_disabled_changed(&self, disabled: bool) -> zbus::Result<()>;
+    }
+}
+
+pub mod sftp {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.sftp",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Sftp {
+        #[zbus(name = "mount")]
+        fn mount(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "unmount")]
+        fn unmount(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "mountAndWait")]
+        fn mount_and_wait(&self) -> zbus::Result<bool>;
+
+        #[zbus(name = "isMounted")]
+        fn is_mounted(&self) -> zbus::Result<bool>;
+
+        #[zbus(name = "startBrowsing")]
+        fn start_browsing(&self) -> zbus::Result<bool>;
+
+        #[zbus(name = "mountPoint")]
+        fn mount_point(&self) -> zbus::Result<String>;
+
+        #[zbus(name = "getMountError")]
+        fn mount_error(&self) -> zbus::Result<String>;
+
+        #[zbus(signal, name = "mounted")]
+        fn mounted(&self) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "unmounted")]
+        fn unmounted(&self) -> zbus::Result<()>;
+    }
+}
+
+pub mod notifications {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.notifications",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Notifications {
+        /// Public IDs of the notifications currently shown on the device.
+        #[zbus(name = "activeNotifications")]
+        fn active_notifications(&self) -> zbus::Result<Vec<String>>;
+
+        #[zbus(name = "sendReply")]
+        fn send_reply(&self, reply_id: &str, message: &str) -> zbus::Result<()>;
+
+        /// Press an action button, by the notification's internal ID and the button's label.
+        #[zbus(name = "sendAction")]
+        fn send_action(&self, key: &str, action: &str) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "notificationPosted")]
+        fn notification_posted(&self, public_id: String) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "notificationUpdated")]
+        fn notification_updated(&self, public_id: String) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "notificationRemoved")]
+        fn notification_removed(&self, public_id: String) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "allNotificationsRemoved")]
+        fn all_notifications_removed(&self) -> zbus::Result<()>;
+    }
+}
+
+/// A single notification, at `<device>/notifications/<public id>`.
+pub mod notification {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.notifications.notification",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Notification {
+        /// The phone's key for the notification.
+        #[zbus(property, name = "internalId")]
+        fn internal_id(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "appName")]
+        fn app_name(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "title")]
+        fn title(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "text")]
+        fn text(&self) -> zbus::Result<String>;
+
+        /// Where the app icon is cached, once it has been received.
+        #[zbus(property, name = "iconPath")]
+        fn icon_path(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "hasIcon")]
+        fn has_icon(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "dismissable")]
+        fn dismissable(&self) -> zbus::Result<bool>;
+
+        /// Sent without alerting, e.g. because it was already shown before connecting.
+        #[zbus(property, name = "silent")]
+        fn silent(&self) -> zbus::Result<bool>;
+
+        /// Empty when the notification can't be replied to.
+        #[zbus(property, name = "replyId")]
+        fn reply_id(&self) -> zbus::Result<String>;
+
+        /// Labels of the notification's action buttons.
+        #[zbus(property, name = "actions")]
+        fn actions(&self) -> zbus::Result<Vec<String>>;
+
+        #[zbus(name = "dismiss")]
+        fn dismiss(&self) -> zbus::Result<()>;
+
+        #[zbus(name = "sendReply")]
+        fn send_reply(&self, message: &str) -> zbus::Result<()>;
+    }
+}
+
+pub mod mprisremote {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.mprisremote",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait MprisRemote {
+        #[zbus(property, name = "playerList")]
+        fn player_list(&self) -> zbus::Result<Vec<String>>;
+
+        #[zbus(property, name = "player")]
+        fn player(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "player")]
+        fn set_player(&self, player: &str) -> zbus::Result<()>;
+
+        #[zbus(property, name = "title")]
+        fn title(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "artist")]
+        fn artist(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "album")]
+        fn album(&self) -> zbus::Result<String>;
+
+        /// Cover of the current track, as a local `file://` URL once
+        /// kdeconnectd has fetched it. Missing on daemons without album art.
+        #[zbus(property, name = "albumArtUrl")]
+        fn album_art_url(&self) -> zbus::Result<String>;
+
+        #[zbus(property, name = "isPlaying")]
+        fn is_playing(&self) -> zbus::Result<bool>;
+
+        /// Track length in milliseconds.
+        #[zbus(property, name = "length")]
+        fn length(&self) -> zbus::Result<i32>;
+
+        /// Playback position in milliseconds.
+        #[zbus(property, name = "position")]
+        fn position(&self) -> zbus::Result<i32>;
+
+        #[zbus(property, name = "position")]
+        fn set_position(&self, position: i32) -> zbus::Result<()>;
+
+        #[zbus(property, name = "volume")]
+        fn volume(&self) -> zbus::Result<i32>;
+
+        #[zbus(property, name = "volume")]
+        fn set_volume(&self, volume: i32) -> zbus::Result<()>;
+
+        #[zbus(property, name = "canPlay")]
+        fn can_play(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "canPause")]
+        fn can_pause(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "canGoNext")]
+        fn can_go_next(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "canGoPrevious")]
+        fn can_go_previous(&self) -> zbus::Result<bool>;
+
+        #[zbus(property, name = "canSeek")]
+        fn can_seek(&self) -> zbus::Result<bool>;
+
+        /// One of `Play`, `Pause`, `PlayPause`, `Stop`, `Next`, `Previous`.
+        #[zbus(name = "sendAction")]
+        fn send_action(&self, action: &str) -> zbus::Result<()>;
+
+        /// Seek relative to the current position, in milliseconds.
+        #[zbus(name = "seek")]
+        fn seek(&self, offset: i32) -> zbus::Result<()>;
+
+        #[zbus(name = "requestPlayerList")]
+        fn request_player_list(&self) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "propertiesChanged")]
+        fn properties_changed(&self) -> zbus::Result<()>;
+    }
+}
+
+/// SMS conversations. Unlike the other plugins this interface lives on the
+/// device object itself rather than on a sub-path.
+pub mod conversations {
+    use zbus::zvariant::OwnedValue;
+
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.conversations",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Conversations {
+        /// The newest message of every known thread.
+        #[zbus(name = "activeConversations")]
+        fn active_conversations(&self) -> zbus::Result<Vec<OwnedValue>>;
+
+        #[zbus(name = "requestAllConversationThreads")]
+        fn request_all_conversation_threads(&self) -> zbus::Result<()>;
+
+        /// Messages arrive through `conversationUpdated`.
+        #[zbus(name = "requestConversation")]
+        fn request_conversation(&self, thread_id: i64, start: i32, end: i32) -> zbus::Result<()>;
+
+        #[zbus(name = "replyToConversation")]
+        fn reply_to_conversation(
+            &self,
+            thread_id: i64,
+            message: &str,
+            attachments: &[zbus::zvariant::Value<'_>],
+        ) -> zbus::Result<()>;
+
+        #[zbus(name = "sendWithoutConversation")]
+        fn send_without_conversation(
+            &self,
+            addresses: &[zbus::zvariant::Value<'_>],
+            message: &str,
+            attachments: &[zbus::zvariant::Value<'_>],
+        ) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "conversationCreated")]
+        fn conversation_created(&self, message: zbus::zvariant::Value<'_>) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "conversationUpdated")]
+        fn conversation_updated(&self, message: zbus::zvariant::Value<'_>) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "conversationRemoved")]
+        fn conversation_removed(&self, thread_id: i64) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "conversationLoaded")]
+        fn conversation_loaded(&self, thread_id: i64, message_count: u64) -> zbus::Result<()>;
+    }
+}
+
+pub mod contacts {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.contacts",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait Contacts {
+        /// Ask the peer to push its contacts into the local vCard cache.
+        #[zbus(name = "synchronizeRemoteWithLocal")]
+        fn synchronize_remote_with_local(&self) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "localCacheSynchronized")]
+        fn local_cache_synchronized(&self, uids: Vec<String>) -> zbus::Result<()>;
+    }
+}
+
+pub mod remotekeyboard {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.remotekeyboard",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait RemoteKeyboard {
+        /// Type `key` on the peer. `special_key` is a KDE Connect special key
+        /// code (0 for none), in which case `key` is left empty.
+        #[zbus(name = "sendKeyPress")]
+        fn send_key_press(
+            &self,
+            key: &str,
+            special_key: i32,
+            shift: bool,
+            ctrl: bool,
+            alt: bool,
+            send_ack: bool,
+        ) -> zbus::Result<()>;
+
+        /// Whether the KDE Connect keyboard is the active input method on the peer.
+        #[zbus(property, name = "remoteState")]
+        fn remote_state(&self) -> zbus::Result<bool>;
+    }
+}
+
+/// Sends `kdeconnect.mousepad.request` packets, driving the peer's pointer.
+pub mod remotecontrol {
+    use std::collections::HashMap;
+    use zbus::zvariant::Value;
+
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.remotecontrol",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait RemoteControl {
+        /// Relative pointer motion, marshalled as a `QPoint`.
+        #[zbus(name = "moveCursor")]
+        fn move_cursor(&self, delta: &(i32, i32)) -> zbus::Result<()>;
+
+        /// Send a raw mousepad request body, such as `{"singleclick": true}`.
+        #[zbus(name = "sendCommand")]
+        fn send_command(&self, body: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+    }
+}
+
+/// Runs `krfb-virtualmonitor` and asks the peer's VNC client to connect to it.
+///
+/// The resolution and scale come from the peer's announced capabilities and
+/// are not exposed over D-Bus.
+pub mod virtualmonitor {
+    #[zbus::proxy(
+        interface = "org.kde.kdeconnect.device.virtualmonitor",
+        default_service = "org.kde.kdeconnect",
+        gen_blocking = false
+    )]
+    pub trait VirtualMonitor {
+        /// Start the virtual display; false when it could not be started.
+        #[zbus(name = "requestVirtualMonitor")]
+        fn request_virtual_monitor(&self) -> zbus::Result<bool>;
+
+        #[zbus(name = "stop")]
+        fn stop(&self) -> zbus::Result<()>;
+
+        /// Whether `krfb-virtualmonitor` is installed on this machine.
+        #[zbus(property, name = "isVirtualMonitorAvailable")]
+        fn is_virtual_monitor_available(&self) -> zbus::Result<bool>;
+
+        /// Whether the peer announced a VNC client to show the display with.
+        #[zbus(property, name = "hasRemoteVncClient")]
+        fn has_remote_vnc_client(&self) -> zbus::Result<bool>;
+    }
+}
+
+/// A local MPRIS2 player; not part of KDE Connect, so it has no default service.
+pub mod media_player {
+    #[zbus::proxy(
+        interface = "org.mpris.MediaPlayer2",
+        default_path = "/org/mpris/MediaPlayer2",
+        gen_blocking = false
+    )]
+    pub trait MediaPlayer2 {
+        #[zbus(property, name = "Identity")]
+        fn identity(&self) -> zbus::Result<String>;
+    }
+}
+
+/// The desktop's notification server; not part of KDE Connect either.
+pub mod desktop_notifications {
+    use std::collections::HashMap;
+
+    use zbus::zvariant::Value;
+
+    #[zbus::proxy(
+        interface = "org.freedesktop.Notifications",
+        default_service = "org.freedesktop.Notifications",
+        default_path = "/org/freedesktop/Notifications",
+        gen_blocking = false
+    )]
+    pub trait DesktopNotifications {
+        /// Show a notification, or replace the one with ID `replaces_id`.
+        #[allow(clippy::too_many_arguments)]
+        #[zbus(name = "Notify")]
+        fn notify(
+            &self,
+            app_name: &str,
+            replaces_id: u32,
+            app_icon: &str,
+            summary: &str,
+            body: &str,
+            actions: &[&str],
+            hints: HashMap<&str, Value<'_>>,
+            expire_timeout: i32,
+        ) -> zbus::Result<u32>;
+
+        #[zbus(name = "CloseNotification")]
+        fn close_notification(&self, id: u32) -> zbus::Result<()>;
+
+        #[zbus(name = "GetCapabilities")]
+        fn get_capabilities(&self) -> zbus::Result<Vec<String>>;
+
+        #[zbus(signal, name = "ActionInvoked")]
+        fn action_invoked(&self, id: u32, action_key: String) -> zbus::Result<()>;
+
+        #[zbus(signal, name = "NotificationClosed")]
+        fn notification_closed(&self, id: u32, reason: u32) -> zbus::Result<()>;
+
+        /// Sent instead of `ActionInvoked` for an inline reply field.
+        #[zbus(signal, name = "NotificationReplied")]
+        fn notification_replied(&self, id: u32, text: String) -> zbus::Result<()>;
+    }
+}
+
+/// Proxies for objects that live under a device's object path.
+pub trait DeviceObject: From<zbus::Proxy<'static>> + zbus::proxy::Defaults {
+    /// Path below `/modules/kdeconnect/devices/<id>`; empty for the device itself.
+    const SUB_PATH: &'static str;
+}
+
+impl DeviceObject for DeviceProxy<'static> {
+    const SUB_PATH: &'static str = "";
+}
+
+impl DeviceObject for ConversationsProxy<'static> {
+    const SUB_PATH: &'static str = "";
+}
+
+impl DeviceObject for BatteryProxy<'static> {
+    const SUB_PATH: &'static str = "battery";
+}
+
+impl DeviceObject for ConnectivityReportProxy<'static> {
+    const SUB_PATH: &'static str = "connectivity_report";
+}
+
+impl DeviceObject for PingProxy<'static> {
+    const SUB_PATH: &'static str = "ping";
+}
+
+impl DeviceObject for FindMyPhoneProxy<'static> {
+    const SUB_PATH: &'static str = "findmyphone";
+}
+
+impl DeviceObject for LockDeviceProxy<'static> {
+    const SUB_PATH: &'static str = "lockdevice";
+}
+
+impl DeviceObject for ShareProxy<'static> {
+    const SUB_PATH: &'static str = "share";
+}
+
+impl DeviceObject for ClipboardProxy<'static> {
+    const SUB_PATH: &'static str = "clipboard";
+}
+
+impl DeviceObject for SftpProxy<'static> {
+    const SUB_PATH: &'static str = "sftp";
+}
+
+impl DeviceObject for NotificationsProxy<'static> {
+    const SUB_PATH: &'static str = "notifications";
+}
+
+impl DeviceObject for MprisRemoteProxy<'static> {
+    const SUB_PATH: &'static str = "mprisremote";
+}
+
+impl DeviceObject for ContactsProxy<'static> {
+    const SUB_PATH: &'static str = "contacts";
+}
+
+impl DeviceObject for RemoteKeyboardProxy<'static> {
+    const SUB_PATH: &'static str = "remotekeyboard";
+}
+
+impl DeviceObject for RemoteControlProxy<'static> {
+    const SUB_PATH: &'static str = "remotecontrol";
+}
+
+impl DeviceObject for VirtualMonitorProxy<'static> {
+    const SUB_PATH: &'static str = "virtualmonitor";
+}
+
+/// Proxy for the daemon object.
+pub async fn daemon(conn: &Connection) -> zbus::Result<DaemonProxy<'static>> {
+    DaemonProxy::builder(conn)
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+}
+
+/// Proxy for a device or one of its plugin objects.
+///
+/// Property caching is disabled: kdeconnectd announces changes through its
+/// own signals instead of `PropertiesChanged`, so cached values go stale.
+pub async fn device_object<P: DeviceObject>(conn: &Connection, device_id: &str) -> zbus::Result<P> {
+    let mut path = device_path(device_id);
+    if !P::SUB_PATH.is_empty() {
+        path.push('/');
+        path.push_str(P::SUB_PATH);
+    }
+
+    zbus::proxy::Builder::<P>::new(conn)
+        .path(path)?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+}
+
+/// Proxy for one of a device's notifications, uncached like [`device_object`].
+pub async fn notification(conn: &Connection, device_id: &str, public_id: &str) -> zbus::Result<NotificationProxy<'static>> {
+    NotificationProxy::builder(conn)
+        .path(format!("{}/notifications/{}", device_path(device_id), public_id))?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+}
@@ -12,122 +12,10 @@ use cosmic::iced::{Alignment, Length, Subscription};
 use cosmic::widget::{self, segmented_button};
 use cosmic::{Application, ApplicationExt, Element};
 use std::collections::HashMap;
-use zbus::Connection;
-
-mod plugin_config;
-use plugin_config::PluginConfigs;
-
-#[derive(Debug, Clone)]
-pub struct Device {
-    id: String,
-    name: String,
-    device_type: String,
-    is_reachable: bool,
-    is_paired: bool,
-    #[allow(dead_code)]
-    is_trusted: bool,
-    battery_level: Option<i32>,
-    is_charging: Option<bool>,
-    #[allow(dead_code)]
-    has_battery: bool,
-    has_ping: bool,
-    #[allow(dead_code)]
-    has_share: bool,
-    has_findmyphone: bool,
-    #[allow(dead_code)]
-    has_sms: bool,
-    #[allow(dead_code)]
-    has_clipboard: bool,
-    #[allow(dead_code)]
-    has_contacts: bool,
-    #[allow(dead_code)]
-    has_mpris: bool,
-    #[allow(dead_code)]
-    has_remote_keyboard: bool,
-    #[allow(dead_code)]
-    has_notifications: bool,
-    #[allow(dead_code)]
-    has_sftp: bool,
-    #[allow(dead_code)]
-    has_presenter: bool,
-    #[allow(dead_code)]
-    has_lockdevice: bool,
-    #[allow(dead_code)]
-    has_virtualmonitor: bool,
-    pairing_requests: i32,
-}
-
-/// Represents the permission/plugin states for a device.
-#[derive(Debug, Clone)]
-pub struct DevicePermissions {
-    // Existing plugins
-    pub sms: bool,
-    pub share: bool,                     // Changed from filesystem - this is kdeconnect_share
-    pub sftp: bool,                      // Added - this is kdeconnect_sftp  
-    pub media_player: bool,
-    pub volume_control: bool,
-    pub connectivity_report: bool,
-    pub remote_keypresses: bool,
-    pub notifications: bool,
-    pub pause_media_calls: bool,
-    pub contacts_sync: bool,
-    pub clipboard: bool,
-    
-    // Missing plugins from KDE Connect
-    pub battery: bool,
-    pub ping: bool,
-    pub findmyphone: bool,
-    pub presenter: bool,
-    pub photo: bool,
-    pub runcommand: bool,
-    pub lockdevice: bool,
-    pub telephony: bool,
-    pub mpris_remote: bool,
-    pub multimedia_receiver: bool,
-    pub screensaver_inhibit: bool,
-    pub virtualmonitor: bool,
-    pub bigscreen: bool,
-    pub mousepad: bool,              // Use phone as mouse/touchpad
-    pub remotecontrol: bool,         // Remote control for media/presentations
-    pub sendnotifications: bool,     // Send notifications TO phone
-}
-
-impl Default for DevicePermissions {
-    fn default() -> Self {
-        Self {
-            // Usually enabled by default
-            sms: false,
-            share: true,                 // Share plugin enabled by default
-            sftp: false,                 // SFTP typically needs explicit enable
-            media_player: true,
-            volume_control: true,
-            connectivity_report: true,
-            remote_keypresses: false,
-            notifications: true,
-            pause_media_calls: true,
-            contacts_sync: false,
-            clipboard: false,
-            
-            // Additional plugins
-            battery: true,
-            ping: true,
-            findmyphone: true,
-            presenter: true,
-            photo: false,
-            runcommand: true,
-            lockdevice: true,
-            telephony: true,
-            mpris_remote: true,
-            multimedia_receiver: true,
-            screensaver_inhibit: false,
-            virtualmonitor: false,
-            bigscreen: false,
-            mousepad: false,              // Disabled by default (security/UX)
-            remotecontrol: false,         // Disabled by default
-            sendnotifications: false,     // Disabled by default
-        }
-    }
-}
+use cosmic_connect_applet::dbus;
+use cosmic_connect_applet::device_state::{self, DeviceEvent};
+use cosmic_connect_applet::models::{Device, PairState, Plugin};
+use cosmic_connect_applet::plugin_config::{self, PluginConfigs};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Page {
@@ -138,21 +26,22 @@ enum Page {
 pub struct KdeConnectSettings {
     core: Core,
     devices: HashMap<String, Device>,
-    permissions: DevicePermissions,
     plugin_configs: PluginConfigs,
     selected_device: Option<String>,
     current_page: segmented_button::SingleSelectModel,
     page_id_paired: segmented_button::Entity,
     page_id_available: segmented_button::Entity,
     is_loading: bool,
-    last_interaction: std::time::Instant,
     // Track which plugin configuration is currently expanded/visible
-    expanded_plugin_config: Option<PermissionType>,
+    expanded_plugin_config: Option<Plugin>,
+    // MPRIS players running on this machine, for the mpriscontrol configuration
+    local_players: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     RefreshDevices,
+    DeviceStateChanged(DeviceEvent),
     DevicesUpdated(Vec<Device>),
     SelectDevice(String),
     DeselectDevice,
@@ -163,12 +52,11 @@ pub enum Message {
     PingDevice(String),
     RingDevice(String),
     PageSelected(segmented_button::Entity),
-    TogglePermission(PermissionType),
-    PermissionsLoaded(DevicePermissions),
+    TogglePermission(Plugin),
     // Plugin configuration messages
-    TogglePluginConfig(PermissionType),  // Toggle configuration visibility for a plugin
+    TogglePluginConfig(Plugin),          // Toggle configuration visibility for a plugin
     UpdateShareDestination(String),
-    SavePluginConfig(PermissionType),    // Save configuration for a specific plugin
+    SavePluginConfig(Plugin),            // Save configuration for a specific plugin
     BrowseShareDestination,              // Open file picker for share plugin
     FolderSelected(Option<String>),      // Result from folder picker
     // Clipboard configuration messages
@@ -199,43 +87,13 @@ pub enum Message {
     RemoveSendNotificationsApp(usize),            // Remove app by index
     UpdateSendNotificationsAppName(usize, String), // Update app name
     ToggleSendNotificationsAppEnabled(usize, bool), // Toggle app enabled
+    // MprisControl configuration messages
+    RefreshLocalPlayers,                          // Look for local MPRIS players again
+    LocalPlayersLoaded(Vec<String>),              // Identities of local MPRIS players
+    ToggleMprisControlPlayer(String, bool),       // Expose or hide a player from the device
     PluginConfigsLoaded(PluginConfigs),
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum PermissionType {
-    // Original plugins
-    SMS,
-    Share,                  // Changed from Filesystem - kdeconnect_share
-    Sftp,                   // Added - kdeconnect_sftp (remote filesystem browser)
-    MediaPlayer,
-    VolumeControl,
-    ConnectivityReport,
-    RemoteKeypresses,
-    Notifications,
-    PauseMediaCalls,
-    ContactsSync,
-    Clipboard,
-    
-    // Additional plugins
-    Battery,
-    Ping,
-    FindMyPhone,
-    Presenter,
-    Photo,
-    RunCommand,
-    LockDevice,
-    Telephony,
-    MprisRemote,
-    MultimediaReceiver,
-    ScreensaverInhibit,
-    VirtualMonitor,
-    Bigscreen,
-    Mousepad,             // Use phone as mouse/touchpad
-    RemoteControl,        // Remote control for media/presentations
-    SendNotifications,    // Send notifications to phone
-}
-
 impl Application for KdeConnectSettings {
     type Executor = cosmic::executor::Default;
     type Flags = Option<String>; // kdeconnect:// URL from notifications
@@ -292,15 +150,14 @@ impl Application for KdeConnectSettings {
         let mut app = KdeConnectSettings {
             core,
             devices: HashMap::new(),
-            permissions: DevicePermissions::default(),
             plugin_configs: PluginConfigs::load(""),  // Will be loaded when device is selected
             selected_device: target_device_id.clone(),
             current_page: model,
             page_id_paired,
             page_id_available,
             is_loading: true,
-            last_interaction: std::time::Instant::now(),
             expanded_plugin_config: None,
+            local_players: Vec::new(),
         };
 
         let title_task = app.set_window_title("KDE Connect Settings".to_string(), app.core.main_window_id().unwrap());
@@ -309,7 +166,7 @@ impl Application for KdeConnectSettings {
         let tasks = vec![
             title_task,
             cosmic::task::future(async move {
-                Message::DevicesUpdated(fetch_devices().await)
+                Message::DevicesUpdated(dbus::fetch_devices().await.unwrap_or_default())
             }),
         ];
 
@@ -317,20 +174,24 @@ impl Application for KdeConnectSettings {
     }
 
     fn update(&mut self, message: Self::Message) -> Task<Self::Message> {
-        // Update last interaction time for any user action
-        self.last_interaction = std::time::Instant::now();
-        
         match message {
             Message::RefreshDevices => {
-                // Reduced from 5 to 1 second to allow refresh after pairing actions
-                if self.last_interaction.elapsed().as_secs() < 1 {
-                    return Task::none();
-                }
                 self.is_loading = true;
                 return cosmic::task::future(async move {
-                    Message::DevicesUpdated(fetch_devices().await)
+                    Message::DevicesUpdated(dbus::fetch_devices().await.unwrap_or_default())
                 });
             }
+            Message::DeviceStateChanged(event) => match event {
+                DeviceEvent::Snapshot(devices) => return self.update(Message::DevicesUpdated(devices)),
+                DeviceEvent::Updated(device) => {
+                    self.devices.insert(device.id.clone(), device);
+                }
+                DeviceEvent::Removed(device_id) => {
+                    self.devices.remove(&device_id);
+                }
+                // Neither the daemon's status nor media state is shown here
+                DeviceEvent::Daemon(_) | DeviceEvent::MediaChanged(_) => {}
+            },
             Message::DevicesUpdated(devices) => {
                 self.devices.clear();
                 for device in devices {
@@ -342,11 +203,10 @@ impl Application for KdeConnectSettings {
                 eprintln!("=== Device Selected: {} ===", device_id);
                 self.selected_device = Some(device_id.clone());
                 
-                // Load the actual plugin states for this device
-                eprintln!("Loading permissions for device: {}", device_id);
+                // Plugin states come with the device; only the configs need loading
                 return cosmic::task::future(async move {
-                    let permissions = load_device_permissions(device_id).await;
-                    Message::PermissionsLoaded(permissions)
+                    let configs = PluginConfigs::load(&device_id);
+                    Message::PluginConfigsLoaded(configs)
                 });
             }
             Message::DeselectDevice => {
@@ -354,44 +214,44 @@ impl Application for KdeConnectSettings {
             }
             Message::PairDevice(device_id) => {
                 return cosmic::task::future(async move {
-                    pair_device(device_id).await;
+                    let _ = dbus::pair_device(device_id).await;
                     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    Message::DevicesUpdated(fetch_devices().await)
+                    Message::DevicesUpdated(dbus::fetch_devices().await.unwrap_or_default())
                 });
             }
             Message::UnpairDevice(device_id) => {
                 self.selected_device = None;
                 return cosmic::task::future(async move {
-                    unpair_device(device_id).await;
+                    let _ = dbus::unpair_device(device_id).await;
                     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    Message::DevicesUpdated(fetch_devices().await)
+                    Message::DevicesUpdated(dbus::fetch_devices().await.unwrap_or_default())
                 });
             }
             Message::AcceptPairing(device_id) => {
                 return cosmic::task::future(async move {
-                    accept_pairing(device_id).await;
+                    let _ = dbus::accept_pairing(device_id).await;
                     // Give pairing state time to propagate through D-Bus
                     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    Message::DevicesUpdated(fetch_devices().await)
+                    Message::DevicesUpdated(dbus::fetch_devices().await.unwrap_or_default())
                 });
             }
             Message::RejectPairing(device_id) => {
                 return cosmic::task::future(async move {
-                    reject_pairing(device_id).await;
+                    let _ = dbus::reject_pairing(device_id).await;
                     // Give pairing state time to propagate through D-Bus
                     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    Message::DevicesUpdated(fetch_devices().await)
+                    Message::DevicesUpdated(dbus::fetch_devices().await.unwrap_or_default())
                 });
             }
             Message::PingDevice(device_id) => {
                 return cosmic::task::future(async move {
-                    ping_device(device_id).await;
+                    let _ = dbus::ping_device(device_id).await;
                     Message::RefreshDevices
                 });
             }
             Message::RingDevice(device_id) => {
                 return cosmic::task::future(async move {
-                    ring_device(device_id).await;
+                    let _ = dbus::ring_device(device_id).await;
                     Message::RefreshDevices
                 });
             }
@@ -402,48 +262,22 @@ impl Application for KdeConnectSettings {
                     self.selected_device = None;
                 }
             }
-            Message::TogglePermission(perm) => {
-                // Update local state immediately for UI responsiveness
-                match perm {
-                    PermissionType::SMS => self.permissions.sms = !self.permissions.sms,
-                    PermissionType::Share => self.permissions.share = !self.permissions.share,
-                    PermissionType::Sftp => self.permissions.sftp = !self.permissions.sftp,
-                    PermissionType::MediaPlayer => self.permissions.media_player = !self.permissions.media_player,
-                    PermissionType::VolumeControl => self.permissions.volume_control = !self.permissions.volume_control,
-                    PermissionType::ConnectivityReport => self.permissions.connectivity_report = !self.permissions.connectivity_report,
-                    PermissionType::RemoteKeypresses => self.permissions.remote_keypresses = !self.permissions.remote_keypresses,
-                    PermissionType::Notifications => self.permissions.notifications = !self.permissions.notifications,
-                    PermissionType::PauseMediaCalls => self.permissions.pause_media_calls = !self.permissions.pause_media_calls,
-                    PermissionType::ContactsSync => self.permissions.contacts_sync = !self.permissions.contacts_sync,
-                    PermissionType::Clipboard => self.permissions.clipboard = !self.permissions.clipboard,
-                    PermissionType::Battery => self.permissions.battery = !self.permissions.battery,
-                    PermissionType::Ping => self.permissions.ping = !self.permissions.ping,
-                    PermissionType::FindMyPhone => self.permissions.findmyphone = !self.permissions.findmyphone,
-                    PermissionType::Presenter => self.permissions.presenter = !self.permissions.presenter,
-                    PermissionType::Photo => self.permissions.photo = !self.permissions.photo,
-                    PermissionType::RunCommand => self.permissions.runcommand = !self.permissions.runcommand,
-                    PermissionType::LockDevice => self.permissions.lockdevice = !self.permissions.lockdevice,
-                    PermissionType::Telephony => self.permissions.telephony = !self.permissions.telephony,
-                    PermissionType::MprisRemote => self.permissions.mpris_remote = !self.permissions.mpris_remote,
-                    PermissionType::MultimediaReceiver => self.permissions.multimedia_receiver = !self.permissions.multimedia_receiver,
-                    PermissionType::ScreensaverInhibit => self.permissions.screensaver_inhibit = !self.permissions.screensaver_inhibit,
-                    PermissionType::VirtualMonitor => self.permissions.virtualmonitor = !self.permissions.virtualmonitor,
-                    PermissionType::Bigscreen => self.permissions.bigscreen = !self.permissions.bigscreen,
-                    PermissionType::Mousepad => self.permissions.mousepad = !self.permissions.mousepad,
-                    PermissionType::RemoteControl => self.permissions.remotecontrol = !self.permissions.remotecontrol,
-                    PermissionType::SendNotifications => self.permissions.sendnotifications = !self.permissions.sendnotifications,
-                }
-
+            Message::TogglePermission(plugin) => {
                 // Apply the change via D-Bus if a device is selected
-                if let Some(device_id) = &self.selected_device {
-                    let device_id_clone = device_id.clone();
-                    let plugin_name = permission_to_plugin_name(&perm);
-                    let enabled = get_permission_state(&self.permissions, &perm);
-                    
-                    eprintln!("Toggling plugin: {} -> {}", plugin_name, enabled);
+                if let Some(device) = self.selected_device.as_ref().and_then(|id| self.devices.get_mut(id)) {
+                    // Update local state immediately for UI responsiveness
+                    let enabled = !device.plugins.is_enabled(plugin);
+                    if enabled {
+                        device.plugins.enabled.insert(plugin);
+                    } else {
+                        device.plugins.enabled.remove(&plugin);
+                    }
+
+                    let device_id_clone = device.id.clone();
+                    eprintln!("Toggling plugin: {} -> {}", plugin.id(), enabled);
                     
                     return cosmic::task::future(async move {
-                        match set_plugin_enabled_internal(device_id_clone.clone(), plugin_name, enabled).await {
+                        match dbus::set_plugin_enabled(device_id_clone.clone(), plugin, enabled).await {
                             Ok(_) => {
                                 eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã…â€œ Plugin state changed successfully");
                                 // Wait a moment for the device to process the change
@@ -451,25 +285,30 @@ impl Application for KdeConnectSettings {
                                 Message::RefreshDevices
                             }
                             Err(e) => {
-                                eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â Failed to change plugin state: {}", e);
+                                eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â Failed to change plugin state: {}", e);
                                 Message::RefreshDevices
                             }
                         }
                     });
                 }
             }
-            Message::PermissionsLoaded(permissions) => {
-                eprintln!("Ã¢Å“â€œ Loaded permissions for selected device");
-                self.permissions = permissions;
-                
-                // Also load plugin configs for the selected device
-                if let Some(device_id) = &self.selected_device {
-                    let device_id = device_id.clone();
-                    return cosmic::task::future(async move {
-                        let configs = PluginConfigs::load(&device_id);
-                        Message::PluginConfigsLoaded(configs)
-                    });
-                }
+            Message::RefreshLocalPlayers => {
+                return cosmic::task::future(async move {
+                    match dbus::list_local_media_players().await {
+                        Ok(players) => Message::LocalPlayersLoaded(players),
+                        Err(e) => {
+                            eprintln!("Ã¢Å“â€” Failed to list local media players: {:?}", e);
+                            Message::LocalPlayersLoaded(Vec::new())
+                        }
+                    }
+                });
+            }
+            Message::LocalPlayersLoaded(players) => {
+                self.local_players = players;
+            }
+            Message::ToggleMprisControlPlayer(player, exposed) => {
+                self.plugin_configs.mpriscontrol.set_excluded(&player, !exposed);
+                eprintln!("MprisControl player {} exposed: {}", player, exposed);
             }
             Message::PluginConfigsLoaded(configs) => {
                 eprintln!("Ã¢Å“â€œ Loaded plugin configurations for selected device");
@@ -477,10 +316,13 @@ impl Application for KdeConnectSettings {
             }
             Message::TogglePluginConfig(plugin_type) => {
                 // Toggle the expanded state for this plugin's configuration
-                if self.expanded_plugin_config.as_ref() == Some(&plugin_type) {
+                if self.expanded_plugin_config == Some(plugin_type) {
                     self.expanded_plugin_config = None;
                 } else {
                     self.expanded_plugin_config = Some(plugin_type);
+                    if plugin_type == Plugin::MprisControl {
+                        return self.update(Message::RefreshLocalPlayers);
+                    }
                 }
             }
             Message::UpdateShareDestination(path) => {
@@ -518,7 +360,7 @@ impl Application for KdeConnectSettings {
                 eprintln!("Clipboard send password: {}", enabled);
             }
             Message::AddRunCommand => {
-                use crate::plugin_config::RemoteCommand;
+                use plugin_config::RemoteCommand;
                 let new_command = RemoteCommand {
                     id: format!("command_{}", self.plugin_configs.runcommand.commands.len()),
                     name: "New Command".to_string(),
@@ -630,7 +472,7 @@ impl Application for KdeConnectSettings {
                 eprintln!("Send notifications - sync icons: {}", enabled);
             }
             Message::UpdateSendNotificationsMinUrgency(level) => {
-                use crate::plugin_config::UrgencyLevel;
+                use plugin_config::UrgencyLevel;
                 self.plugin_configs.sendnotifications.min_urgency = UrgencyLevel::from_i32(level);
                 eprintln!("Send notifications - min urgency: {:?}", self.plugin_configs.sendnotifications.min_urgency);
             }
@@ -639,7 +481,7 @@ impl Application for KdeConnectSettings {
                 eprintln!("Send notifications - use blocklist: {}", is_blocklist);
             }
             Message::AddSendNotificationsApp => {
-                use crate::plugin_config::AppNotificationSetting;
+                use plugin_config::AppNotificationSetting;
                 let new_app = AppNotificationSetting {
                     app_name: "App Name".to_string(),
                     enabled: true,
@@ -670,7 +512,7 @@ impl Application for KdeConnectSettings {
                     
                     return cosmic::task::future(async move {
                         match plugin_type {
-                            PermissionType::Share => {
+                            Plugin::Share => {
                                 match configs.share.save(&device_id) {
                                     Ok(_) => {
                                         eprintln!("Ã¢Å“â€œ Saved share plugin configuration");
@@ -680,7 +522,7 @@ impl Application for KdeConnectSettings {
                                     }
                                 }
                             }
-                            PermissionType::Clipboard => {
+                            Plugin::Clipboard => {
                                 match configs.clipboard.save(&device_id) {
                                     Ok(_) => {
                                         eprintln!("Ã¢Å“â€œ Saved clipboard plugin configuration");
@@ -690,7 +532,7 @@ impl Application for KdeConnectSettings {
                                     }
                                 }
                             }
-                            PermissionType::RunCommand => {
+                            Plugin::RunCommand => {
                                 match configs.runcommand.save(&device_id) {
                                     Ok(_) => {
                                         eprintln!("Ã¢Å“â€œ Saved runcommand plugin configuration");
@@ -700,7 +542,7 @@ impl Application for KdeConnectSettings {
                                     }
                                 }
                             }
-                            PermissionType::PauseMediaCalls => {
+                            Plugin::PauseMusic => {
                                 match configs.pausemusic.save(&device_id) {
                                     Ok(_) => {
                                         eprintln!("Ã¢Å“â€œ Saved pausemusic plugin configuration");
@@ -710,7 +552,7 @@ impl Application for KdeConnectSettings {
                                     }
                                 }
                             }
-                            PermissionType::FindMyPhone => {
+                            Plugin::FindMyPhone => {
                                 match configs.findmyphone.save(&device_id) {
                                     Ok(_) => {
                                         eprintln!("Ã¢Å“â€œ Saved findmyphone plugin configuration");
@@ -720,7 +562,7 @@ impl Application for KdeConnectSettings {
                                     }
                                 }
                             }
-                            PermissionType::SendNotifications => {
+                            Plugin::SendNotifications => {
                                 match configs.sendnotifications.save(&device_id) {
                                     Ok(_) => {
                                         eprintln!("Ã¢Å“â€œ Saved sendnotifications plugin configuration");
@@ -730,6 +572,16 @@ impl Application for KdeConnectSettings {
                                     }
                                 }
                             }
+                            Plugin::MprisControl => {
+                                match configs.mpriscontrol.save(&device_id) {
+                                    Ok(_) => {
+                                        eprintln!("Ã¢Å“â€œ Saved mpriscontrol plugin configuration");
+                                    }
+                                    Err(e) => {
+                                        eprintln!("Ã¢Å“â€” Failed to save mpriscontrol plugin configuration: {:?}", e);
+                                    }
+                                }
+                            }
                             _ => {
                                 eprintln!("Configuration save not implemented for {:?}", plugin_type);
                             }
@@ -790,9 +642,9 @@ impl Application for KdeConnectSettings {
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        // Refresh every 10 seconds instead of 3 to reduce interruptions
-        cosmic::iced::time::every(std::time::Duration::from_secs(10))
-            .map(|_| Message::RefreshDevices)
+        // Kept current by the shared device-state service, as in the applet
+        Subscription::run_with_id("device-state", device_state::watch())
+            .map(Message::DeviceStateChanged)
     }
 }
 
@@ -828,7 +680,7 @@ impl KdeConnectSettings {
                 
                 let button = widget::button::custom(
                     widget::row()
-                        .push(widget::icon::from_name(device_icon(&device.device_type)).size(24))
+                        .push(widget::icon::from_name(device.device_icon()).size(24))
                         .push(
                             widget::column()
                                 .push(widget::text(&device.name).size(14))
@@ -930,7 +782,7 @@ impl KdeConnectSettings {
             let device_id_reject = device.id.clone();
 
             let mut device_row = widget::row()
-                .push(widget::icon::from_name(device_icon(&device.device_type)).size(32))
+                .push(widget::icon::from_name(device.device_icon()).size(32))
                 .push(
                     widget::column()
                         .push(widget::text(&device.name).size(16))
@@ -941,10 +793,18 @@ impl KdeConnectSettings {
                 .spacing(spacing.space_m)
                 .align_y(Alignment::Center);
 
-            if device.pairing_requests > 0 {
+            // Both ends show the same key; it should match before accepting
+            let verification = device.verification_key.as_ref().map(|key| {
+                widget::text(format!("Verification key: {}", key))
+                    .size(14)
+                    .font(cosmic::font::bold())
+            });
+
+            if device.pair_state == PairState::RequestedByPeer {
                 device_row = device_row.push(
                     widget::column()
                         .push(widget::text("Wants to pair").size(12))
+                        .push_maybe(verification)
                         .push(
                             widget::row()
                                 .push(widget::button::suggested("Accept").on_press(Message::AcceptPairing(device_id_pair)))
@@ -953,6 +813,13 @@ impl KdeConnectSettings {
                         )
                         .spacing(spacing.space_xxs)
                 );
+            } else if device.pair_state == PairState::Requested {
+                device_row = device_row.push(
+                    widget::column()
+                        .push(widget::text("Waiting for the device to accept…").size(12))
+                        .push_maybe(verification)
+                        .spacing(spacing.space_xxs)
+                );
             } else {
                 device_row = device_row.push(
                     widget::button::suggested("Pair").on_press(Message::PairDevice(device_id))
@@ -986,7 +853,7 @@ impl KdeConnectSettings {
         // Device header
         details = details.push(
             widget::row()
-                .push(widget::icon::from_name(device_icon(&device.device_type)).size(48))
+                .push(widget::icon::from_name(device.device_icon()).size(48))
                 .push(
                     widget::column()
                         .push(widget::text(&device.name).size(20))
@@ -1026,7 +893,7 @@ impl KdeConnectSettings {
                     .push(widget::text("Battery").size(12))
                     .push(
                         widget::row()
-                            .push(widget::icon::from_name(battery_icon(level, charging)).size(16))
+                            .push(widget::icon::from_name(device.battery_icon()).size(16))
                             .push(widget::text(battery_text).size(14))
                             .spacing(spacing.space_xs)
                             .align_y(Alignment::Center)
@@ -1042,53 +909,25 @@ impl KdeConnectSettings {
         details = details.push(widget::text("Available Plugins").size(14).font(cosmic::font::bold()));
         details = details.push(widget::text("Enable or disable plugins and configure their settings").size(12));
 
-        // Define plugins with their properties: (name, description, enabled_state, permission_type, has_config)
-        let plugins = vec![
-            // Sorted alphabetically to match KDE Connect
-            ("Battery monitor", "Show your phone battery next to your computer battery", self.permissions.battery, PermissionType::Battery, false),
-            ("Bigscreen voice control", "Send voice commands to your TV running Plasma Bigscreen", self.permissions.bigscreen, PermissionType::Bigscreen, false),
-            ("Clipboard", "Share the clipboard between devices", self.permissions.clipboard, PermissionType::Clipboard, true),
-            ("Connectivity monitor", "Show your phone's network signal strength", self.permissions.connectivity_report, PermissionType::ConnectivityReport, false),
-            ("Contacts", "Synchronize Contacts from the Connected Device to the Desktop", self.permissions.contacts_sync, PermissionType::ContactsSync, false),
-            ("Find this device", "Find this device by making it play an alarm sound", self.permissions.findmyphone, PermissionType::FindMyPhone, true),
-            ("Host remote commands", "Trigger commands predefined on the remote device", self.permissions.runcommand, PermissionType::RunCommand, true),
-            ("Inhibit screensaver", "Inhibit the screensaver when the device is connected", self.permissions.screensaver_inhibit, PermissionType::ScreensaverInhibit, false),
-            ("LockDevice", "Locks your systems", self.permissions.lockdevice, PermissionType::LockDevice, false),
-            ("ModemManager Telephony integration", "Show notifications for incoming calls", self.permissions.telephony, PermissionType::Telephony, false),
-            ("MprisRemote", "Control MPRIS services", self.permissions.mpris_remote, PermissionType::MprisRemote, false),
-            ("Multimedia control receiver", "Remote control your music and videos", self.permissions.multimedia_receiver, PermissionType::MultimediaReceiver, false),
-            ("Mousepad", "Use your phone as a wireless mouse and keyboard", self.permissions.mousepad, PermissionType::Mousepad, false),
-            ("Pause media during calls", "Pause music/videos during a phone call", self.permissions.pause_media_calls, PermissionType::PauseMediaCalls, true),
-            ("Photo", "Use a connected device to take a photo", self.permissions.photo, PermissionType::Photo, false),
-            ("Ping", "Send and receive pings", self.permissions.ping, PermissionType::Ping, false),
-            ("Presenter", "Use your mobile device to point to things on the screen", self.permissions.presenter, PermissionType::Presenter, false),
-            ("Receive notifications", "Show device's notifications on this computer and keep them in sync", self.permissions.notifications, PermissionType::Notifications, false),
-            ("Remote control", "Control system volume and multimedia players remotely", self.permissions.remotecontrol, PermissionType::RemoteControl, false),
-            ("Remote filesystem browser", "Browse files on the device remotely using SFTP", self.permissions.sftp, PermissionType::Sftp, false),
-            ("Remote keypresses", "Receive remote keyboard input", self.permissions.remote_keypresses, PermissionType::RemoteKeypresses, false),
-            ("Share", "Send and receive files", self.permissions.share, PermissionType::Share, true),
-            ("Send notifications", "Send notifications to your phone from this computer", self.permissions.sendnotifications, PermissionType::SendNotifications, true),
-            ("SMS Messages", "Send and receive SMS messages", self.permissions.sms, PermissionType::SMS, false),
-            ("Virtual monitor", "Use your phone as a virtual monitor", self.permissions.virtualmonitor, PermissionType::VirtualMonitor, false),
-            ("Volume control", "Control device volume remotely", self.permissions.volume_control, PermissionType::VolumeControl, false),
-        ];
-
-
-        for (label, description, enabled, perm_type, has_config) in plugins {
-            let perm_clone = perm_type.clone();
+        // Plugin::ALL is sorted alphabetically to match KDE Connect. Only plugins
+        // the device supports are listed, unless the daemon didn't say which
+        let listed = |plugin: &Plugin| device.plugins.supported.is_empty() || device.plugins.is_supported(*plugin);
+        for plugin in Plugin::ALL.into_iter().filter(listed) {
+            let info = plugin.info();
+            let enabled = device.plugins.is_enabled(plugin);
             
             // Main plugin row
             let toggle = widget::toggler(enabled)
-                .on_toggle(move |_| Message::TogglePermission(perm_clone.clone()));
+                .on_toggle(move |_| Message::TogglePermission(plugin));
             
             // Create description column with optional note
             let mut desc_column = widget::column()
                 .spacing(spacing.space_xxs)
-                .push(widget::text(label).size(13))
-                .push(widget::text(description).size(11));
+                .push(widget::text(info.name).size(13))
+                .push(widget::text(info.description).size(11));
             
             // Add special note for SFTP plugin
-            if matches!(perm_type, PermissionType::Sftp) {
+            if plugin == Plugin::Sftp {
                 desc_column = desc_column.push(
                     widget::text("Note: Requires allowing permissions to device's filesystem if available")
                         .size(10)
@@ -1102,11 +941,10 @@ impl KdeConnectSettings {
             
             // Add configure button if plugin has configuration options
             // Configure button goes BEFORE toggle (between description and toggle)
-            if has_config && enabled {
-                let perm_clone3 = perm_type.clone();
+            if has_config(plugin) && enabled {
                 plugin_row = plugin_row.push(
                     widget::button::text("Configure")
-                        .on_press(Message::TogglePluginConfig(perm_clone3))
+                        .on_press(Message::TogglePluginConfig(plugin))
                         .class(cosmic::theme::Button::Text)
                 );
             }
@@ -1120,8 +958,8 @@ impl KdeConnectSettings {
             details = details.push(plugin_container);
             
             // Show configuration UI if this plugin is expanded
-            if self.expanded_plugin_config.as_ref() == Some(&perm_type) {
-                let config_ui = self.view_plugin_config(&perm_type);
+            if self.expanded_plugin_config == Some(plugin) {
+                let config_ui = self.view_plugin_config(plugin);
                 details = details.push(config_ui);
             }
             
@@ -1135,14 +973,14 @@ impl KdeConnectSettings {
         if device.is_reachable {
             let mut actions = widget::row().spacing(spacing.space_xs);
             
-            if device.has_ping {
+            if device.plugins.is_loaded(Plugin::Ping) {
                 actions = actions.push(
                     widget::button::standard("Send Ping")
                         .on_press(Message::PingDevice(device_id_ping))
                 );
             }
             
-            if device.has_findmyphone {
+            if device.plugins.is_loaded(Plugin::FindMyPhone) {
                 actions = actions.push(
                     widget::button::standard("Ring Device")
                         .on_press(Message::RingDevice(device_id_ring))
@@ -1166,11 +1004,11 @@ impl KdeConnectSettings {
     }
 
     /// View configuration UI for a specific plugin type
-    fn view_plugin_config<'a>(&'a self, plugin_type: &PermissionType) -> Element<'a, Message> {
+    fn view_plugin_config<'a>(&'a self, plugin: Plugin) -> Element<'a, Message> {
         let spacing = cosmic::theme::active().cosmic().spacing;
         
-        match plugin_type {
-            PermissionType::Share => {
+        match plugin {
+            Plugin::Share => {
                 // Share plugin configuration (file transfer)
                 widget::container(
                     widget::column()
@@ -1194,7 +1032,7 @@ impl KdeConnectSettings {
                                 )
                                 .push(
                                     widget::button::suggested("Save")
-                                        .on_press(Message::SavePluginConfig(PermissionType::Share))
+                                        .on_press(Message::SavePluginConfig(Plugin::Share))
                                 )
                                 .spacing(spacing.space_xs)
                         )
@@ -1204,7 +1042,7 @@ impl KdeConnectSettings {
                 .width(Length::Fill)
                 .into()
             }
-            PermissionType::Clipboard => {
+            Plugin::Clipboard => {
                 // Clipboard plugin configuration
                 widget::container(
                     widget::column()
@@ -1255,7 +1093,7 @@ impl KdeConnectSettings {
                                 .push(widget::Space::with_width(Length::Fill))
                                 .push(
                                     widget::button::suggested("Save")
-                                        .on_press(Message::SavePluginConfig(PermissionType::Clipboard))
+                                        .on_press(Message::SavePluginConfig(Plugin::Clipboard))
                                 )
                         )
                         .padding([spacing.space_s, spacing.space_m])
@@ -1264,7 +1102,7 @@ impl KdeConnectSettings {
                 .width(Length::Fill)
                 .into()
             }
-            PermissionType::RunCommand => {
+            Plugin::RunCommand => {
                 // RunCommand plugin configuration (host remote commands)
                 let mut column = widget::column()
                     .spacing(spacing.space_xs)
@@ -1338,7 +1176,7 @@ impl KdeConnectSettings {
                         .push(widget::Space::with_width(Length::Fill))
                         .push(
                             widget::button::suggested("Save")
-                                .on_press(Message::SavePluginConfig(PermissionType::RunCommand))
+                                .on_press(Message::SavePluginConfig(Plugin::RunCommand))
                         )
                         .spacing(spacing.space_xs)
                 );
@@ -1348,7 +1186,7 @@ impl KdeConnectSettings {
                     .width(Length::Fill)
                     .into()
             }
-            PermissionType::PauseMediaCalls => {
+            Plugin::PauseMusic => {
                 // PauseMusic plugin configuration (pause media during calls)
                 widget::container(
                     widget::column()
@@ -1464,7 +1302,7 @@ impl KdeConnectSettings {
                                 .push(widget::Space::with_width(Length::Fill))
                                 .push(
                                     widget::button::suggested("Save")
-                                        .on_press(Message::SavePluginConfig(PermissionType::PauseMediaCalls))
+                                        .on_press(Message::SavePluginConfig(Plugin::PauseMusic))
                                 )
                         )
                         .padding([spacing.space_s, spacing.space_m])
@@ -1473,7 +1311,7 @@ impl KdeConnectSettings {
                 .width(Length::Fill)
                 .into()
             }
-            PermissionType::FindMyPhone => {
+            Plugin::FindMyPhone => {
                 // FindMyPhone plugin configuration (find this device)
                 widget::container(
                     widget::column()
@@ -1521,7 +1359,7 @@ impl KdeConnectSettings {
                                 .push(widget::Space::with_width(Length::Fill))
                                 .push(
                                     widget::button::suggested("Save")
-                                        .on_press(Message::SavePluginConfig(PermissionType::FindMyPhone))
+                                        .on_press(Message::SavePluginConfig(Plugin::FindMyPhone))
                                 )
                         )
                         .padding([spacing.space_s, spacing.space_m])
@@ -1530,7 +1368,7 @@ impl KdeConnectSettings {
                 .width(Length::Fill)
                 .into()
             }
-            PermissionType::SendNotifications => {
+            Plugin::SendNotifications => {
                 // SendNotifications plugin configuration
                 let mut column = widget::column()
                     .spacing(spacing.space_xs)
@@ -1712,7 +1550,80 @@ impl KdeConnectSettings {
                         .push(widget::Space::with_width(Length::Fill))
                         .push(
                             widget::button::suggested("Save")
-                                .on_press(Message::SavePluginConfig(PermissionType::SendNotifications))
+                                .on_press(Message::SavePluginConfig(Plugin::SendNotifications))
+                        )
+                        .spacing(spacing.space_xs)
+                );
+                
+                widget::container(column.padding([spacing.space_s, spacing.space_m]))
+                    .class(cosmic::theme::Container::Card)
+                    .width(Length::Fill)
+                    .into()
+            }
+            Plugin::MprisControl => {
+                // MprisControl plugin configuration
+                let config = &self.plugin_configs.mpriscontrol;
+                
+                // Excluded players stay listed while closed, so they can be exposed again
+                let mut players = self.local_players.clone();
+                for player in &config.excluded_players {
+                    if !players.contains(player) {
+                        players.push(player.clone());
+                    }
+                }
+                players.sort();
+                
+                let mut column = widget::column()
+                    .spacing(spacing.space_xs)
+                    .push(
+                        widget::text("Local Media Players").size(12).font(cosmic::font::bold())
+                    )
+                    .push(
+                        widget::text("Choose which players on this computer the device can control").size(11)
+                    )
+                    .push(widget::Space::with_height(Length::Fixed(spacing.space_xs as f32)));
+                
+                if players.is_empty() {
+                    column = column.push(widget::text("No media players are running").size(10));
+                }
+                
+                for player in players {
+                    let status = if self.local_players.contains(&player) {
+                        "Running"
+                    } else {
+                        "Not running"
+                    };
+                    let exposed = !config.is_excluded(&player);
+                    
+                    column = column.push(
+                        widget::row()
+                            .push(
+                                widget::column()
+                                    .push(widget::text(player.clone()).size(12))
+                                    .push(widget::text(status).size(10))
+                                    .spacing(spacing.space_xxxs)
+                                    .width(Length::Fill)
+                            )
+                            .push(
+                                widget::toggler(exposed)
+                                    .on_toggle(move |e| Message::ToggleMprisControlPlayer(player.clone(), e))
+                            )
+                            .spacing(spacing.space_m)
+                            .align_y(cosmic::iced::Alignment::Center)
+                    );
+                }
+                
+                column = column.push(widget::Space::with_height(Length::Fixed(spacing.space_s as f32)));
+                column = column.push(
+                    widget::row()
+                        .push(
+                            widget::button::standard("Refresh")
+                                .on_press(Message::RefreshLocalPlayers)
+                        )
+                        .push(widget::Space::with_width(Length::Fill))
+                        .push(
+                            widget::button::suggested("Save")
+                                .on_press(Message::SavePluginConfig(Plugin::MprisControl))
                         )
                         .spacing(spacing.space_xs)
                 );
@@ -1735,501 +1646,18 @@ impl KdeConnectSettings {
     }
 }
 
-fn device_icon(device_type: &str) -> &'static str {
-    match device_type {
-        "phone" => "phone-symbolic",
-        "tablet" => "tablet-symbolic",
-        "desktop" => "computer-symbolic",
-        "laptop" => "computer-symbolic",
-        _ => "phone-symbolic",
-    }
-}
-
-fn battery_icon(level: i32, charging: bool) -> &'static str {
-    if charging {
-        return "battery-full-charging-symbolic";
-    }
-    match level {
-        0..=20 => "battery-level-20-symbolic",
-        21..=40 => "battery-level-40-symbolic",
-        41..=60 => "battery-level-60-symbolic",
-        61..=80 => "battery-level-80-symbolic",
-        _ => "battery-level-100-symbolic",
-    }
-}
-
-// D-Bus helper functions
-
-async fn fetch_devices() -> Vec<Device> {
-    let mut devices = Vec::new();
-
-    match Connection::session().await {
-        Ok(conn) => {
-            let daemon_path = "/modules/kdeconnect";
-            let daemon_interface = "org.kde.kdeconnect.daemon";
-            
-            let result = conn.call_method(
-                Some("org.kde.kdeconnect"),
-                daemon_path,
-                Some(daemon_interface),
-                "devices",
-                &(false, false)
-            ).await;
-            
-            match result {
-                Ok(reply) => {
-                    let body = reply.body();
-                    match body.deserialize::<Vec<String>>() {
-                        Ok(device_ids) => {
-                            for device_id in device_ids {
-                                let path = format!("/modules/kdeconnect/devices/{}", device_id);
-                                
-                                let name = get_device_property(&conn, &path, "name").await.unwrap_or_else(|_| "Unknown".to_string());
-                                let is_reachable = get_device_property_bool(&conn, &path, "isReachable").await.unwrap_or(false);
-                                let is_paired = get_device_property_bool(&conn, &path, "isPaired").await.unwrap_or(false);
-                                let is_trusted = get_device_property_bool(&conn, &path, "isTrusted").await.unwrap_or(false);
-                                let device_type = get_device_property(&conn, &path, "type").await.unwrap_or_else(|_| "phone".to_string());
-                                
-                                let has_battery = check_plugin(&conn, &path, "kdeconnect_battery").await;
-                                let has_ping = check_plugin(&conn, &path, "kdeconnect_ping").await;
-                                let has_share = check_plugin(&conn, &path, "kdeconnect_share").await;
-                                let has_findmyphone = check_plugin(&conn, &path, "kdeconnect_findmyphone").await;
-                                let has_sms = check_plugin(&conn, &path, "kdeconnect_sms").await;
-                                let has_clipboard = check_plugin(&conn, &path, "kdeconnect_clipboard").await;
-                                let has_contacts = check_plugin(&conn, &path, "kdeconnect_contacts").await;
-                                let has_mpris = check_plugin(&conn, &path, "kdeconnect_mpriscontrol").await;
-                                let has_remote_keyboard = check_plugin(&conn, &path, "kdeconnect_remotekeyboard").await;
-                                let has_notifications = check_plugin(&conn, &path, "kdeconnect_notifications").await;
-                                let has_sftp = check_plugin(&conn, &path, "kdeconnect_sftp").await;
-                                let has_presenter = check_plugin(&conn, &path, "kdeconnect_presenter").await;
-                                let has_lockdevice = check_plugin(&conn, &path, "kdeconnect_lockdevice").await;
-                                let has_virtualmonitor = check_plugin(&conn, &path, "kdeconnect_virtualmonitor").await;
-                                
-                                let (battery_level, is_charging) = if has_battery {
-                                    let level = get_device_property_int(&conn, &path, "charge").await.ok();
-                                    let charging = get_device_property_bool(&conn, &path, "isCharging").await.ok();
-                                    (level, charging)
-                                } else {
-                                    (None, None)
-                                };
-
-                                // Check BOTH pairing directions:
-                                // - isPairRequestedByPeer: Phone is requesting to pair with PC
-                                // - isPairRequested: PC is requesting to pair with Phone
-                                let pairing_requested_by_peer = get_device_property_bool(&conn, &path, "isPairRequestedByPeer").await.unwrap_or(false);
-                                let pairing_requested_by_us = get_device_property_bool(&conn, &path, "isPairRequested").await.unwrap_or(false);
-                                
-                                // Show Accept/Reject buttons if EITHER direction has a pending request
-                                let pairing_requested = pairing_requested_by_peer || pairing_requested_by_us;
-                                let pairing_requests = if pairing_requested { 1 } else { 0 };
-                                
-                                if pairing_requested {
-                                    eprintln!("=== Pairing Request Detected ===");
-                                    eprintln!("Device: {} ({})", name, device_id);
-                                    if pairing_requested_by_peer {
-                                        eprintln!("Direction: Phone ÃƒÆ’Ã‚Â¢ÃƒÂ¢Ã¢â€šÂ¬Ã‚Â ÃƒÂ¢Ã¢â€šÂ¬Ã¢â€žÂ¢ PC (isPairRequestedByPeer: true)");
-                                    }
-                                    if pairing_requested_by_us {
-                                        eprintln!("Direction: PC ÃƒÆ’Ã‚Â¢ÃƒÂ¢Ã¢â€šÂ¬Ã‚Â ÃƒÂ¢Ã¢â€šÂ¬Ã¢â€žÂ¢ Phone (isPairRequested: true)");
-                                    }
-                                }
-
-                                devices.push(Device {
-                                    id: device_id,
-                                    name,
-                                    device_type,
-                                    is_reachable,
-                                    is_paired,
-                                    is_trusted,
-                                    battery_level,
-                                    is_charging,
-                                    has_battery,
-                                    has_ping,
-                                    has_share,
-                                    has_findmyphone,
-                                    has_sms,
-                                    has_clipboard,
-                                    has_contacts,
-                                    has_mpris,
-                                    has_remote_keyboard,
-                                    has_notifications,
-                                    has_sftp,
-                                    has_presenter,
-                                    has_lockdevice,
-                                    has_virtualmonitor,
-                                    pairing_requests,
-                                });
-                            }
-                        }
-                        Err(_) => {}
-                    }
-                }
-                Err(_) => {}
-            }
-        }
-        Err(_) => {}
-    }
-
-    devices
-}
-
-async fn get_device_property(conn: &Connection, path: &str, property: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &("org.kde.kdeconnect.device", property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::Str(s) = value {
-        Ok(s.to_string())
-    } else {
-        Err("Not a string".into())
-    }
-}
-
-async fn get_device_property_bool(conn: &Connection, path: &str, property: &str) -> Result<bool, Box<dyn std::error::Error>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &("org.kde.kdeconnect.device", property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::Bool(b) = value {
-        Ok(b)
-    } else {
-        Err("Not a bool".into())
-    }
-}
-
-async fn get_device_property_int(conn: &Connection, path: &str, property: &str) -> Result<i32, Box<dyn std::error::Error>> {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.freedesktop.DBus.Properties"),
-        "Get",
-        &("org.kde.kdeconnect.device", property)
-    ).await?;
-    
-    let body = result.body();
-    let value: zbus::zvariant::Value = body.deserialize()?;
-    
-    if let zbus::zvariant::Value::I32(i) = value {
-        Ok(i)
-    } else {
-        Err("Not an int".into())
-    }
-}
-
-async fn check_plugin(conn: &Connection, path: &str, plugin: &str) -> bool {
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path,
-        Some("org.kde.kdeconnect.device"),
-        "hasPlugin",
-        &(plugin,)
-    ).await;
-    
-    if let Ok(reply) = result {
-        let body = reply.body();
-        body.deserialize::<bool>().unwrap_or(false)
-    } else {
-        false
-    }
-}
-
-async fn pair_device(device_id: String) {
-    eprintln!("=== Requesting Pairing ===");
-    eprintln!("Device: {}", device_id);
-    
-    if let Ok(conn) = Connection::session().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        let result = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device"),
-            "requestPair",
-            &()
-        ).await;
-        
-        match result {
-            Ok(_) => eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã…â€œ Pairing request sent successfully"),
-            Err(e) => eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â Failed to send pairing request: {:?}", e),
-        }
-    } else {
-        eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â Failed to connect to D-Bus");
-    }
-}
-
-async fn unpair_device(device_id: String) {
-    eprintln!("=== Unpairing Device ===");
-    eprintln!("Device: {}", device_id);
-    
-    if let Ok(conn) = Connection::session().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        let result = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device"),
-            "unpair",
-            &()
-        ).await;
-        
-        match result {
-            Ok(_) => eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã…â€œ Device unpaired successfully"),
-            Err(e) => eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â Failed to unpair device: {:?}", e),
-        }
-    }
-}
-
-async fn accept_pairing(device_id: String) {
-    eprintln!("=== Accepting Pairing ===");
-    eprintln!("Device: {}", device_id);
-    
-    if let Ok(conn) = Connection::session().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        let result = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device"),
-            "acceptPairing",
-            &()
-        ).await;
-        
-        match result {
-            Ok(_) => eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã…â€œ Pairing accepted successfully"),
-            Err(e) => eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â Failed to accept pairing: {:?}", e),
-        }
-    }
-}
-
-async fn reject_pairing(device_id: String) {
-    eprintln!("=== Rejecting Pairing ===");
-    eprintln!("Device: {}", device_id);
-    
-    if let Ok(conn) = Connection::session().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        
-        let result = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device"),
-            "rejectPairing",
-            &()
-        ).await;
-        
-        match result {
-            Ok(_) => eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã…â€œ Pairing rejected successfully"),
-            Err(e) => eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â Failed to reject pairing: {:?}", e),
-        }
-    }
-}
-
-async fn ping_device(device_id: String) {
-    if let Ok(conn) = Connection::session().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        let _ = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.ping"),
-            "sendPing",
-            &()
-        ).await;
-    }
-}
-
-async fn ring_device(device_id: String) {
-    if let Ok(conn) = Connection::session().await {
-        let path = format!("/modules/kdeconnect/devices/{}", device_id);
-        let _ = conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path.as_str(),
-            Some("org.kde.kdeconnect.device.findmyphone"),
-            "ring",
-            &()
-        ).await;
-    }
-}
-
-/// Maps a PermissionType to its corresponding KDE Connect plugin name.
-fn permission_to_plugin_name(perm: &PermissionType) -> String {
-    match perm {
-        PermissionType::SMS => "kdeconnect_sms",
-        PermissionType::Share => "kdeconnect_share",           // File sharing (send/receive)
-        PermissionType::Sftp => "kdeconnect_sftp",             // Remote filesystem browser
-        PermissionType::MediaPlayer => "kdeconnect_mpriscontrol",
-        PermissionType::VolumeControl => "kdeconnect_systemvolume",
-        PermissionType::ConnectivityReport => "kdeconnect_connectivity_report",
-        PermissionType::RemoteKeypresses => "kdeconnect_remotekeyboard",
-        PermissionType::Notifications => "kdeconnect_notifications",
-        PermissionType::PauseMediaCalls => "kdeconnect_pausemusic",
-        PermissionType::ContactsSync => "kdeconnect_contacts",
-        PermissionType::Clipboard => "kdeconnect_clipboard",
-        PermissionType::Battery => "kdeconnect_battery",
-        PermissionType::Ping => "kdeconnect_ping",
-        PermissionType::FindMyPhone => "kdeconnect_findmyphone",
-        PermissionType::Presenter => "kdeconnect_presenter",
-        PermissionType::Photo => "kdeconnect_photo",
-        PermissionType::RunCommand => "kdeconnect_runcommand",
-        PermissionType::LockDevice => "kdeconnect_lockdevice",
-        PermissionType::Telephony => "kdeconnect_telephony",
-        PermissionType::MprisRemote => "kdeconnect_mprisremote",
-        PermissionType::MultimediaReceiver => "kdeconnect_mpriscontrol",
-        PermissionType::ScreensaverInhibit => "kdeconnect_screensaver_inhibit",
-        PermissionType::VirtualMonitor => "kdeconnect_virtualmonitor",
-        PermissionType::Bigscreen => "kdeconnect_bigscreen",
-        PermissionType::Mousepad => "kdeconnect_mousepad",
-        PermissionType::RemoteControl => "kdeconnect_remotecontrol",
-        PermissionType::SendNotifications => "kdeconnect_sendnotifications",
-    }.to_string()
-}
-
-/// Gets the current state of a permission from DevicePermissions.
-fn get_permission_state(permissions: &DevicePermissions, perm: &PermissionType) -> bool {
-    match perm {
-        PermissionType::SMS => permissions.sms,
-        PermissionType::Share => permissions.share,
-        PermissionType::Sftp => permissions.sftp,
-        PermissionType::MediaPlayer => permissions.media_player,
-        PermissionType::VolumeControl => permissions.volume_control,
-        PermissionType::ConnectivityReport => permissions.connectivity_report,
-        PermissionType::RemoteKeypresses => permissions.remote_keypresses,
-        PermissionType::Notifications => permissions.notifications,
-        PermissionType::PauseMediaCalls => permissions.pause_media_calls,
-        PermissionType::ContactsSync => permissions.contacts_sync,
-        PermissionType::Clipboard => permissions.clipboard,
-        PermissionType::Battery => permissions.battery,
-        PermissionType::Ping => permissions.ping,
-        PermissionType::FindMyPhone => permissions.findmyphone,
-        PermissionType::Presenter => permissions.presenter,
-        PermissionType::Photo => permissions.photo,
-        PermissionType::RunCommand => permissions.runcommand,
-        PermissionType::LockDevice => permissions.lockdevice,
-        PermissionType::Telephony => permissions.telephony,
-        PermissionType::MprisRemote => permissions.mpris_remote,
-        PermissionType::MultimediaReceiver => permissions.multimedia_receiver,
-        PermissionType::ScreensaverInhibit => permissions.screensaver_inhibit,
-        PermissionType::VirtualMonitor => permissions.virtualmonitor,
-        PermissionType::Bigscreen => permissions.bigscreen,
-        PermissionType::Mousepad => permissions.mousepad,
-        PermissionType::RemoteControl => permissions.remotecontrol,
-        PermissionType::SendNotifications => permissions.sendnotifications,
-    }
-}
-
-/// Internal function to set plugin enabled state via D-Bus.
-async fn set_plugin_enabled_internal(device_id: String, plugin_name: String, enabled: bool) -> Result<(), String> {
-    eprintln!("=== Setting Plugin State ===");
-    eprintln!("Device: {}", device_id);
-    eprintln!("Plugin: {}", plugin_name);
-    eprintln!("Enabled: {}", enabled);
-    
-    let conn = Connection::session().await
-        .map_err(|e| format!("D-Bus connection failed: {:?}", e))?;
-    
-    let path = format!("/modules/kdeconnect/devices/{}", device_id);
-    
-    let result = conn.call_method(
-        Some("org.kde.kdeconnect"),
-        path.as_str(),
-        Some("org.kde.kdeconnect.device"),
-        "setPluginEnabled",
-        &(plugin_name.as_str(), enabled)
-    ).await;
-    
-    match result {
-        Ok(_) => {
-            eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã…â€œ Plugin {} {} successfully", 
-                plugin_name, 
-                if enabled { "enabled" } else { "disabled" }
-            );
-            Ok(())
-        }
-        Err(e) => {
-            let err_msg = format!("Failed to set plugin state: {:?}", e);
-            eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â {}", err_msg);
-            Err(err_msg)
-        }
-    }
-}
-
-/// Loads the actual plugin states for a device from KDE Connect.
-async fn load_device_permissions(device_id: String) -> DevicePermissions {
-    eprintln!("=== Loading Device Permissions ===");
-    eprintln!("Device: {}", device_id);
-    
-    let conn = match Connection::session().await {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â D-Bus connection failed: {:?}", e);
-            return DevicePermissions::default();
-        }
-    };
-    
-    let path = format!("/modules/kdeconnect/devices/{}", device_id);
-    
-    // Helper function to check if a plugin is enabled
-    async fn check_plugin_enabled(conn: &Connection, path: &str, plugin_name: &str) -> bool {
-        match conn.call_method(
-            Some("org.kde.kdeconnect"),
-            path,
-            Some("org.kde.kdeconnect.device"),
-            "isPluginEnabled",
-            &(plugin_name,)
-        ).await {
-            Ok(reply) => {
-                let body = reply.body();
-                body.deserialize::<bool>().unwrap_or(false)
-            }
-            Err(e) => {
-                eprintln!("ÃƒÆ’Ã‚Â¢Ãƒâ€¦Ã¢â‚¬Å“ÃƒÂ¢Ã¢â€šÂ¬Ã¢â‚¬Â Failed to check plugin {}: {:?}", plugin_name, e);
-                false
-            }
-        }
-    }
-    
-    DevicePermissions {
-        sms: check_plugin_enabled(&conn, &path, "kdeconnect_sms").await,
-        share: check_plugin_enabled(&conn, &path, "kdeconnect_share").await,        // File sharing
-        sftp: check_plugin_enabled(&conn, &path, "kdeconnect_sftp").await,          // Remote filesystem
-        media_player: check_plugin_enabled(&conn, &path, "kdeconnect_mpriscontrol").await,
-        volume_control: check_plugin_enabled(&conn, &path, "kdeconnect_systemvolume").await,
-        connectivity_report: check_plugin_enabled(&conn, &path, "kdeconnect_connectivity_report").await,
-        remote_keypresses: check_plugin_enabled(&conn, &path, "kdeconnect_remotekeyboard").await,
-        notifications: check_plugin_enabled(&conn, &path, "kdeconnect_notifications").await,
-        pause_media_calls: check_plugin_enabled(&conn, &path, "kdeconnect_pausemusic").await,
-        contacts_sync: check_plugin_enabled(&conn, &path, "kdeconnect_contacts").await,
-        clipboard: check_plugin_enabled(&conn, &path, "kdeconnect_clipboard").await,
-        
-        battery: check_plugin_enabled(&conn, &path, "kdeconnect_battery").await,
-        ping: check_plugin_enabled(&conn, &path, "kdeconnect_ping").await,
-        findmyphone: check_plugin_enabled(&conn, &path, "kdeconnect_findmyphone").await,
-        presenter: check_plugin_enabled(&conn, &path, "kdeconnect_presenter").await,
-        photo: check_plugin_enabled(&conn, &path, "kdeconnect_photo").await,
-        runcommand: check_plugin_enabled(&conn, &path, "kdeconnect_runcommand").await,
-        lockdevice: check_plugin_enabled(&conn, &path, "kdeconnect_lockdevice").await,
-        telephony: check_plugin_enabled(&conn, &path, "kdeconnect_telephony").await,
-        mpris_remote: check_plugin_enabled(&conn, &path, "kdeconnect_mprisremote").await,
-        multimedia_receiver: check_plugin_enabled(&conn, &path, "kdeconnect_mpriscontrol").await,
-        screensaver_inhibit: check_plugin_enabled(&conn, &path, "kdeconnect_screensaver_inhibit").await,
-        virtualmonitor: check_plugin_enabled(&conn, &path, "kdeconnect_virtualmonitor").await,
-        bigscreen: check_plugin_enabled(&conn, &path, "kdeconnect_bigscreen").await,
-        mousepad: check_plugin_enabled(&conn, &path, "kdeconnect_mousepad").await,
-        remotecontrol: check_plugin_enabled(&conn, &path, "kdeconnect_remotecontrol").await,
-        sendnotifications: check_plugin_enabled(&conn, &path, "kdeconnect_sendnotifications").await,
-    }
+/// Whether the settings app has a configuration panel for the plugin.
+fn has_config(plugin: Plugin) -> bool {
+    matches!(
+        plugin,
+        Plugin::Clipboard
+            | Plugin::FindMyPhone
+            | Plugin::RunCommand
+            | Plugin::PauseMusic
+            | Plugin::Share
+            | Plugin::SendNotifications
+            | Plugin::MprisControl
+    )
 }
 
 fn main() -> cosmic::iced::Result {
@@ -0,0 +1,148 @@
+// src/sftp.rs
+//! Mounting a device's storage over SFTP.
+//!
+//! kdeconnectd runs sshfs for the sftp plugin; this keeps track of what came
+//! of it. A mount whose connection died still shows up as mounted, but any
+//! access to it hangs or fails, so mounts are checked before they are used.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+
+use crate::dbus::{get_connection, KdeConnectError};
+use crate::proxies::{self, SftpProxy};
+
+/// `mountAndWait` gives up on its own after about ten seconds
+const MOUNT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Reading a dead sshfs mount blocks instead of failing
+const ACCESS_TIMEOUT: Duration = Duration::from_secs(3);
+/// sshfs can take a moment to answer after the mount appears
+const ACCESS_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MountState {
+    Unmounted,
+    Mounting,
+    Mounted(PathBuf),
+    /// Mounted, but the connection behind it is gone
+    Stale,
+    Failed(String),
+}
+
+async fn sftp_object(device_id: &str) -> Result<SftpProxy<'static>, KdeConnectError> {
+    let conn = get_connection().await?;
+    Ok(proxies::device_object(&conn, device_id).await?)
+}
+
+/// The device's current mount, checking that it still answers
+pub async fn state(device_id: &str) -> MountState {
+    match sftp_object(device_id).await {
+        Ok(sftp) => current_state(&sftp).await,
+        Err(e) => MountState::Failed(e.to_string()),
+    }
+}
+
+async fn current_state(sftp: &SftpProxy<'_>) -> MountState {
+    match sftp.is_mounted().await {
+        Ok(true) => {}
+        Ok(false) => return MountState::Unmounted,
+        Err(e) => return MountState::Failed(KdeConnectError::from(e).to_string()),
+    }
+
+    match sftp.mount_point().await {
+        Ok(mount_point) if !mount_point.is_empty() && accessible(Path::new(&mount_point)).await => {
+            MountState::Mounted(PathBuf::from(mount_point))
+        }
+        _ => MountState::Stale,
+    }
+}
+
+/// Mount the device, replacing a stale mount first
+pub async fn mount(device_id: String) -> MountState {
+    let sftp = match sftp_object(&device_id).await {
+        Ok(sftp) => sftp,
+        Err(e) => return MountState::Failed(e.to_string()),
+    };
+
+    match current_state(&sftp).await {
+        MountState::Mounted(path) => return MountState::Mounted(path),
+        MountState::Stale => {
+            eprintln!("Replacing stale mount of {}", device_id);
+            if let Err(e) = sftp.unmount().await {
+                eprintln!("✗ Failed to unmount stale mount: {:?}", e);
+            }
+        }
+        _ => {}
+    }
+
+    match tokio::time::timeout(MOUNT_TIMEOUT, sftp.mount_and_wait()).await {
+        Ok(Ok(true)) => {}
+        Ok(Ok(false)) => {
+            let reason = sftp.mount_error().await
+                .ok()
+                .filter(|error| !error.is_empty())
+                .unwrap_or_else(|| "Could not mount the device".to_string());
+            return MountState::Failed(reason);
+        }
+        Ok(Err(e)) => return MountState::Failed(KdeConnectError::from(e).to_string()),
+        Err(_) => return MountState::Failed("Mounting timed out".to_string()),
+    }
+
+    let mount_point = match sftp.mount_point().await {
+        Ok(mount_point) if !mount_point.is_empty() => PathBuf::from(mount_point),
+        Ok(_) => return MountState::Failed("The device has no mount point".to_string()),
+        Err(e) => return MountState::Failed(KdeConnectError::from(e).to_string()),
+    };
+
+    for attempt in 1..=ACCESS_ATTEMPTS {
+        if accessible(&mount_point).await {
+            return MountState::Mounted(mount_point);
+        }
+        if attempt < ACCESS_ATTEMPTS {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+    MountState::Stale
+}
+
+pub async fn unmount(device_id: String) -> Result<(), KdeConnectError> {
+    let sftp = sftp_object(&device_id).await?;
+    sftp.unmount().await?;
+    Ok(())
+}
+
+/// Whether the mount point can be listed
+async fn accessible(path: &Path) -> bool {
+    let path = path.to_path_buf();
+    let listing = tokio::task::spawn_blocking(move || std::fs::read_dir(path).is_ok());
+    matches!(tokio::time::timeout(ACCESS_TIMEOUT, listing).await, Ok(Ok(true)))
+}
+
+/// Mount changes kdeconnectd reports for a device, such as the unmount when
+/// the connection drops, for an iced subscription
+pub fn watch(device_id: String) -> impl Stream<Item = (String, MountState)> {
+    async_stream::stream! {
+        let sftp = match sftp_object(&device_id).await {
+            Ok(sftp) => sftp,
+            Err(e) => {
+                eprintln!("Can't watch the mount of {}: {}", device_id, e);
+                return;
+            }
+        };
+        let (Ok(mounted), Ok(unmounted)) = (sftp.receive_mounted().await, sftp.receive_unmounted().await) else {
+            eprintln!("Can't watch the mount of {}", device_id);
+            return;
+        };
+
+        let mut events = futures::stream::select(mounted.map(|_| true), unmounted.map(|_| false));
+        while let Some(is_mounted) = events.next().await {
+            let state = if is_mounted {
+                current_state(&sftp).await
+            } else {
+                MountState::Unmounted
+            };
+            yield (device_id.clone(), state);
+        }
+    }
+}
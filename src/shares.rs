@@ -0,0 +1,121 @@
+// src/shares.rs
+//! Text, links and files sent to devices.
+//!
+//! Content that parses as a URL goes out through the share plugin's
+//! `shareUrl`, which opens it on the phone; anything else through
+//! `shareText`. Recently sent items are kept in
+//! `$XDG_DATA_HOME/cosmic-connect/recent-shares.json`, newest first, so they
+//! can be sent again.
+
+use std::io;
+use std::path::PathBuf;
+
+/// Recent items kept
+pub const RECENT_LIMIT: usize = 10;
+
+/// The URL in `text`, if it is nothing but a URL
+///
+/// Accepts `scheme:rest` with a well-formed scheme; web and FTP links also
+/// need a host.
+pub fn link(text: &str) -> Option<&str> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let (scheme, rest) = text.split_once(':')?;
+    let well_formed = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !well_formed || rest.is_empty() {
+        return None;
+    }
+
+    match scheme.to_ascii_lowercase().as_str() {
+        "http" | "https" | "ftp" => {
+            let host = rest.strip_prefix("//")?.split(['/', '?', '#']).next()?;
+            (!host.is_empty()).then_some(text)
+        }
+        // A bare `word:word` is more likely text than a URL
+        "mailto" | "tel" | "sms" | "geo" | "magnet" | "spotify" => Some(text),
+        _ => rest.starts_with("//").then_some(text),
+    }
+}
+
+/// The `file://` URL a local file is shared with, percent-encoded so names
+/// with `#`, `%`, `?` or spaces arrive intact. URLs are kept as they are.
+pub fn file_url(path_or_url: &str) -> String {
+    if path_or_url.starts_with('/') {
+        if let Ok(url) = url::Url::from_file_path(path_or_url) {
+            return url.to_string();
+        }
+    }
+    path_or_url.to_string()
+}
+
+fn recent_path() -> PathBuf {
+    let base = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            PathBuf::from(home).join(".local/share")
+        });
+
+    base.join("cosmic-connect").join("recent-shares.json")
+}
+
+/// Load the recently sent items, newest first
+pub fn load_recent() -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(recent_path()) else {
+        return Vec::new();
+    };
+
+    match serde_json::from_str::<serde_json::Value>(&content) {
+        Ok(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .take(RECENT_LIMIT)
+            .collect(),
+        _ => {
+            eprintln!("Ignoring unreadable recent shares");
+            Vec::new()
+        }
+    }
+}
+
+/// Move `content` to the front of the recent items and save them
+pub fn remember(recent: &mut Vec<String>, content: &str) -> io::Result<()> {
+    recent.retain(|item| item != content);
+    recent.insert(0, content.to_string());
+    recent.truncate(RECENT_LIMIT);
+
+    let path = recent_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::Value::from(recent.clone()).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link() {
+        assert_eq!(link(" https://example.com/a?b=c \n"), Some("https://example.com/a?b=c"));
+        assert_eq!(link("mailto:someone@example.com"), Some("mailto:someone@example.com"));
+        assert_eq!(link("https://"), None);
+        assert_eq!(link("note: buy milk"), None);
+        assert_eq!(link("ratio:2"), None);
+    }
+
+    #[test]
+    fn test_file_url() {
+        assert_eq!(
+            file_url("/home/user/50% off #1?.pdf"),
+            "file:///home/user/50%25%20off%20%231%3F.pdf"
+        );
+        assert_eq!(file_url("https://example.com/a%20b"), "https://example.com/a%20b");
+    }
+}
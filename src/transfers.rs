@@ -0,0 +1,442 @@
+// src/transfers.rs
+//! File transfer progress and history.
+//!
+//! kdeconnectd reports sent and received files through KDE's job tracker:
+//! every transfer asks `org.kde.JobViewServer` for a job view and keeps it
+//! updated until it ends. Plasma normally provides that service; the applet
+//! serves it instead, so transfers show progress in the device card and can
+//! be cancelled from there.
+//!
+//! Finished transfers are kept in `$XDG_DATA_HOME/cosmic-connect/transfers.json`.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::Stream;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::sync::Mutex;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
+use zbus::{fdo, interface, Connection, ObjectServer};
+
+use crate::dbus::KdeConnectError;
+
+const SERVICE: &str = "org.kde.JobViewServer";
+const SERVER_PATH: &str = "/JobViewServer";
+const VIEW_PATH_PREFIX: &str = "/io/github/M4LC0ntent/CosmicConnect/JobView";
+
+/// Capability bit of jobs that can be cancelled
+const KILLABLE: i32 = 0x0001;
+/// `terminate` error code of a cancelled job (`KJob::KilledJobError`)
+const KILLED: u32 = 1;
+
+/// Oldest entries are dropped past this many
+const HISTORY_LIMIT: usize = 200;
+
+lazy_static::lazy_static! {
+    /// Connection owning the job tracker name, for cancelling transfers
+    static ref SERVER: Mutex<Option<Connection>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Incoming,
+    Outgoing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferState {
+    Running,
+    Finished,
+    Cancelled,
+    Failed(String),
+}
+
+/// A transfer as reported through its job view
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    pub id: u32,
+    pub title: String,
+    /// Description fields, as (label, value)
+    pub details: Vec<(String, String)>,
+    /// Where a received file is saved
+    pub dest_url: Option<String>,
+    pub processed_bytes: u64,
+    pub total_bytes: u64,
+    pub can_cancel: bool,
+    pub state: TransferState,
+}
+
+impl Transfer {
+    fn new(id: u32, can_cancel: bool) -> Self {
+        Self {
+            id,
+            title: String::new(),
+            details: Vec::new(),
+            dest_url: None,
+            processed_bytes: 0,
+            total_bytes: 0,
+            can_cancel,
+            state: TransferState::Running,
+        }
+    }
+
+    /// Only jobs receiving files announce a destination
+    pub fn direction(&self) -> Direction {
+        if self.dest_url.is_some() {
+            Direction::Incoming
+        } else {
+            Direction::Outgoing
+        }
+    }
+
+    /// The local file: where a received file is saved, or the file being sent
+    pub fn path(&self) -> Option<PathBuf> {
+        self.dest_url
+            .iter()
+            .chain(self.details.iter().map(|(_, value)| value))
+            .find_map(|value| local_path(value))
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path()
+            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| self.title.clone())
+    }
+
+    /// Fraction done, 0.0 while the size is unknown
+    pub fn progress(&self) -> f32 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        (self.processed_bytes as f64 / self.total_bytes as f64).clamp(0.0, 1.0) as f32
+    }
+
+    /// kdeconnectd names the device in the title and description
+    pub fn mentions(&self, device_name: &str) -> bool {
+        !device_name.is_empty()
+            && (self.title.contains(device_name) || self.details.iter().any(|(_, value)| value == device_name))
+    }
+
+    fn apply(&mut self, properties: &HashMap<String, OwnedValue>) {
+        for (key, value) in properties {
+            match key.as_str() {
+                "title" => self.title = text(value).unwrap_or_default(),
+                "totalBytes" => self.total_bytes = number(value).unwrap_or(self.total_bytes),
+                "processedBytes" => self.processed_bytes = number(value).unwrap_or(self.processed_bytes),
+                "destUrl" => self.dest_url = text(value).filter(|url| !url.is_empty()),
+                _ => {
+                    // descriptionLabel1/descriptionValue1, descriptionLabel2/...
+                    let Some((field, index)) = description_field(key) else {
+                        continue;
+                    };
+                    if self.details.len() < index {
+                        self.details.resize(index, (String::new(), String::new()));
+                    }
+                    let detail = &mut self.details[index - 1];
+                    let value = text(value).unwrap_or_default();
+                    if field == "Label" {
+                        detail.0 = value;
+                    } else {
+                        detail.1 = value;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TransferEvent {
+    Changed(Transfer),
+    Ended(Transfer),
+}
+
+/// Stream of transfer events for an iced subscription.
+///
+/// Ends right away if another job tracker, such as Plasma's, owns the name.
+pub fn watch() -> impl Stream<Item = TransferEvent> {
+    async_stream::stream! {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        match serve(tx).await {
+            Ok(conn) => {
+                eprintln!("Tracking transfers as {}", SERVICE);
+                *SERVER.lock().await = Some(conn);
+            }
+            Err(e) => {
+                eprintln!("Can't track transfers, {} is unavailable: {:?}", SERVICE, e);
+                return;
+            }
+        }
+
+        while let Some(event) = rx.recv().await {
+            yield event;
+        }
+    }
+}
+
+async fn serve(tx: UnboundedSender<TransferEvent>) -> zbus::Result<Connection> {
+    zbus::connection::Builder::session()?
+        .name(SERVICE)?
+        .serve_at(SERVER_PATH, JobViewServer { tx, next_id: 1 })?
+        .build()
+        .await
+}
+
+/// Ask kdeconnectd to cancel a running transfer
+pub async fn cancel(id: u32) -> Result<(), KdeConnectError> {
+    let server = SERVER.lock().await;
+    let conn = server
+        .as_ref()
+        .ok_or_else(|| KdeConnectError::Failed("Transfers are not being tracked".to_string()))?;
+
+    let iface = conn.object_server().interface::<_, JobView>(view_path(id)).await?;
+    JobView::cancel_requested(iface.signal_emitter()).await?;
+    Ok(())
+}
+
+fn view_path(id: u32) -> OwnedObjectPath {
+    ObjectPath::from_string_unchecked(format!("{}/{}", VIEW_PATH_PREFIX, id)).into()
+}
+
+/// `org.kde.JobViewServerV2`, handing out one job view per transfer
+struct JobViewServer {
+    tx: UnboundedSender<TransferEvent>,
+    next_id: u32,
+}
+
+#[interface(name = "org.kde.JobViewServerV2")]
+impl JobViewServer {
+    async fn request_view(
+        &mut self,
+        #[zbus(object_server)] server: &ObjectServer,
+        _desktop_entry: &str,
+        capabilities: i32,
+        hints: HashMap<String, OwnedValue>,
+    ) -> fdo::Result<OwnedObjectPath> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let mut transfer = Transfer::new(id, capabilities & KILLABLE != 0);
+        transfer.apply(&hints);
+
+        let path = view_path(id);
+        server
+            .at(
+                &path,
+                JobView {
+                    transfer,
+                    tx: self.tx.clone(),
+                },
+            )
+            .await?;
+        Ok(path)
+    }
+}
+
+/// `org.kde.JobViewV3`, updated by kdeconnectd while a transfer runs
+struct JobView {
+    transfer: Transfer,
+    tx: UnboundedSender<TransferEvent>,
+}
+
+#[interface(name = "org.kde.JobViewV3")]
+impl JobView {
+    fn update(&mut self, properties: HashMap<String, OwnedValue>) {
+        self.transfer.apply(&properties);
+        let _ = self.tx.send(TransferEvent::Changed(self.transfer.clone()));
+    }
+
+    async fn terminate(
+        &mut self,
+        #[zbus(connection)] conn: &Connection,
+        error_code: u32,
+        error_message: String,
+        _hints: HashMap<String, OwnedValue>,
+    ) {
+        self.transfer.state = match error_code {
+            0 => TransferState::Finished,
+            KILLED => TransferState::Cancelled,
+            _ => TransferState::Failed(error_message),
+        };
+        let _ = self.tx.send(TransferEvent::Ended(self.transfer.clone()));
+
+        // Not from inside this call, which holds the view
+        let conn = conn.clone();
+        let path = view_path(self.transfer.id);
+        tokio::spawn(async move {
+            let _ = conn.object_server().remove::<JobView, _>(&path).await;
+        });
+    }
+
+    #[zbus(signal)]
+    async fn cancel_requested(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn suspend_requested(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn resume_requested(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+fn text(value: &OwnedValue) -> Option<String> {
+    <&str>::try_from(value).ok().map(str::to_string)
+}
+
+fn number(value: &OwnedValue) -> Option<u64> {
+    u64::try_from(value)
+        .ok()
+        .or_else(|| u32::try_from(value).ok().map(u64::from))
+        .or_else(|| i64::try_from(value).ok().and_then(|n| u64::try_from(n).ok()))
+}
+
+/// `descriptionLabel2` -> ("Label", 2)
+fn description_field(key: &str) -> Option<(&str, usize)> {
+    let rest = key.strip_prefix("description")?;
+    let split = rest.find(|c: char| c.is_ascii_digit())?;
+    let (field, index) = rest.split_at(split);
+    let index = index.parse().ok().filter(|&i| i > 0)?;
+    matches!(field, "Label" | "Value").then_some((field, index))
+}
+
+fn local_path(value: &str) -> Option<PathBuf> {
+    if let Some(path) = value.strip_prefix("file://") {
+        let path = urlencoding::decode(path).map(|p| p.into_owned()).unwrap_or_else(|_| path.to_string());
+        return Some(PathBuf::from(path));
+    }
+    value.starts_with('/').then(|| PathBuf::from(value))
+}
+
+/// The name a received file was meant to have, when kdeconnectd saved it
+/// under another one because that name was taken
+pub fn collision(path: &Path) -> Option<String> {
+    let name = original_name(path.file_name()?.to_str()?)?;
+    path.with_file_name(&name).exists().then_some(name)
+}
+
+/// `photo.jpg` for `photo (1).jpg`, the way KDE suggests a free name
+fn original_name(file_name: &str) -> Option<String> {
+    let (before, rest) = file_name.split_at(file_name.rfind(" (")?);
+    let (number, after) = rest[2..].split_once(')')?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!("{}{}", before, after))
+}
+
+/// A finished transfer, as kept in the history
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub file_name: String,
+    pub size: u64,
+    pub direction: Direction,
+    pub device_id: String,
+    pub device_name: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub path: Option<PathBuf>,
+    pub succeeded: bool,
+}
+
+impl HistoryEntry {
+    pub fn new(transfer: &Transfer, device_id: &str, device_name: &str) -> Self {
+        Self {
+            file_name: transfer.file_name(),
+            size: transfer.total_bytes,
+            direction: transfer.direction(),
+            device_id: device_id.to_string(),
+            device_name: device_name.to_string(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            path: transfer.path(),
+            succeeded: transfer.state == TransferState::Finished,
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "fileName": self.file_name,
+            "size": self.size,
+            "direction": match self.direction {
+                Direction::Incoming => "incoming",
+                Direction::Outgoing => "outgoing",
+            },
+            "deviceId": self.device_id,
+            "deviceName": self.device_name,
+            "timestamp": self.timestamp,
+            "path": self.path.as_ref().map(|p| p.to_string_lossy().into_owned()),
+            "succeeded": self.succeeded,
+        })
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let text = |key: &str| value.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+        Some(Self {
+            file_name: text("fileName")?,
+            size: value.get("size").and_then(|v| v.as_u64()).unwrap_or(0),
+            direction: match text("direction")?.as_str() {
+                "incoming" => Direction::Incoming,
+                _ => Direction::Outgoing,
+            },
+            device_id: text("deviceId")?,
+            device_name: text("deviceName").unwrap_or_default(),
+            timestamp: value.get("timestamp").and_then(|v| v.as_u64()).unwrap_or(0),
+            path: text("path").map(PathBuf::from),
+            succeeded: value.get("succeeded").and_then(|v| v.as_bool()).unwrap_or(true),
+        })
+    }
+}
+
+fn history_path() -> PathBuf {
+    let base = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+            PathBuf::from(home).join(".local/share")
+        });
+
+    base.join("cosmic-connect").join("transfers.json")
+}
+
+/// Load the history, oldest first
+pub fn load_history() -> Vec<HistoryEntry> {
+    let Ok(content) = std::fs::read_to_string(history_path()) else {
+        return Vec::new();
+    };
+    let Ok(serde_json::Value::Array(entries)) = serde_json::from_str(&content) else {
+        eprintln!("Ignoring unreadable transfer history");
+        return Vec::new();
+    };
+
+    entries.iter().filter_map(HistoryEntry::from_json).collect()
+}
+
+/// Save the history, keeping only the newest entries
+pub fn save_history(history: &[HistoryEntry]) -> io::Result<()> {
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let start = history.len().saturating_sub(HISTORY_LIMIT);
+    let entries: Vec<_> = history[start..].iter().map(HistoryEntry::to_json).collect();
+    std::fs::write(&path, serde_json::Value::Array(entries).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_original_name() {
+        assert_eq!(original_name("photo (1).jpg").as_deref(), Some("photo.jpg"));
+        assert_eq!(original_name("backup (12).tar.gz").as_deref(), Some("backup.tar.gz"));
+        assert_eq!(original_name("notes (draft).txt"), None);
+        assert_eq!(original_name("photo.jpg"), None);
+    }
+}
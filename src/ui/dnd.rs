@@ -0,0 +1,112 @@
+// src/ui/dnd.rs
+//! Payloads dropped onto a device card.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use cosmic::iced::clipboard::mime::AllowedMimeTypes;
+
+const URI_LIST: &str = "text/uri-list";
+const TEXT: [&str; 3] = ["text/plain;charset=utf-8", "text/plain", "UTF8_STRING"];
+
+/// What was dropped on a device
+#[derive(Debug, Clone)]
+pub enum DroppedItems {
+    /// Local paths and remote URLs from a `text/uri-list`
+    Uris(Vec<String>),
+    Text(String),
+}
+
+impl AllowedMimeTypes for DroppedItems {
+    fn allowed() -> Cow<'static, [String]> {
+        Cow::Owned(std::iter::once(URI_LIST).chain(TEXT).map(str::to_string).collect())
+    }
+}
+
+impl TryFrom<(Vec<u8>, String)> for DroppedItems {
+    type Error = String;
+
+    fn try_from((data, mime): (Vec<u8>, String)) -> Result<Self, Self::Error> {
+        let text = String::from_utf8(data).map_err(|e| e.to_string())?;
+
+        if mime == URI_LIST {
+            let uris = parse_uri_list(&text);
+            if uris.is_empty() {
+                return Err("Dropped URI list is empty".to_string());
+            }
+            Ok(Self::Uris(uris))
+        } else if text.trim().is_empty() {
+            Err("Dropped text is empty".to_string())
+        } else {
+            Ok(Self::Text(text))
+        }
+    }
+}
+
+/// Entries of a `text/uri-list`, with `file://` URIs turned into local paths
+pub fn parse_uri_list(list: &str) -> Vec<String> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|uri| match uri.strip_prefix("file://") {
+            // Only local files are sent; `file://host/...` keeps its host part
+            Some(path) if path.starts_with('/') => urlencoding::decode(path)
+                .map(|p| p.into_owned())
+                .unwrap_or_else(|_| path.to_string()),
+            _ => uri.to_string(),
+        })
+        .collect()
+}
+
+/// The files to send for dropped paths, with folders replaced by the files
+/// inside them. The phone receives them flat, without the folder structure.
+pub fn expand_folders(paths: Vec<String>) -> Vec<String> {
+    let mut files = Vec::new();
+    for path in paths {
+        collect_files(Path::new(&path), &mut files);
+    }
+    files
+}
+
+fn collect_files(path: &Path, files: &mut Vec<String>) {
+    if !path.is_dir() {
+        files.push(path.to_string_lossy().into_owned());
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(path) else {
+        eprintln!("Can't read dropped folder {}", path.display());
+        return;
+    };
+    let mut children: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    children.sort();
+    for child in children {
+        // Don't follow links out of the dropped folder
+        if child.is_symlink() && child.is_dir() {
+            continue;
+        }
+        collect_files(&child, files);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uri_list() {
+        let list = "# comment\r\nfile:///home/user/My%20Photo.jpg\r\nhttps://example.com/a%20b\r\n\r\n";
+        assert_eq!(
+            parse_uri_list(list),
+            vec!["/home/user/My Photo.jpg", "https://example.com/a%20b"]
+        );
+    }
+
+    #[test]
+    fn test_dropped_file_is_shared_with_its_uri() {
+        let uri = "file:///home/user/50%25%20off%20%231%3F.pdf";
+        let paths = parse_uri_list(uri);
+        assert_eq!(paths, vec!["/home/user/50% off #1?.pdf"]);
+        assert_eq!(crate::shares::file_url(&paths[0]), uri);
+    }
+}
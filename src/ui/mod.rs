@@ -1,2 +1,3 @@
 // src/ui/mod.rs
+pub mod dnd;
 pub mod popup;
\ No newline at end of file
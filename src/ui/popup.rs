@@ -1,10 +1,44 @@
 // src/ui/popup.rs
 use cosmic::iced::{Alignment, Length};
 use cosmic::{widget, Element};
-use std::collections::HashMap;
-use crate::{models::Device, messages::Message};
+use std::collections::{HashMap, HashSet};
+use crate::{dbus::DaemonStatus, models::{Device, PairState, Plugin}, messages::Message};
+use crate::clipboard::ClipboardItem;
+use crate::phone_notifications::PhoneNotification;
+use crate::sftp::MountState;
+use crate::shares;
+use crate::transfers::{Direction, HistoryEntry, Transfer, TransferState};
+use crate::ui::dnd::DroppedItems;
 
-pub fn create_popup_view<'a>(devices: &'a HashMap<String, Device>, expanded_device: Option<&'a String>, expanded_player_menu: Option<&'a String>) -> Element<'a, Message> {
+/// Finished transfers listed per device until "Show all" is pressed
+const RECENT_TRANSFERS: usize = 5;
+/// Phone notifications listed per device
+const VISIBLE_NOTIFICATIONS: usize = 8;
+
+/// Applet state the popup shows, borrowed for one render
+pub struct PopupState<'a> {
+    pub devices: &'a HashMap<String, Device>,
+    pub expanded_device: Option<&'a String>,
+    pub expanded_player_menu: Option<&'a String>,
+    pub toasts: &'a HashMap<String, String>, // device_id -> last error
+    pub daemon_status: DaemonStatus,
+    pub seek_drag: Option<&'a (String, i64)>, // device_id, position (ms)
+    pub virtual_displays: &'a HashSet<String>,
+    pub mounts: &'a HashMap<String, MountState>,
+    pub active_transfers: &'a HashMap<u32, (String, Transfer)>, // transfer id -> device_id, transfer
+    pub transfer_history: &'a [HistoryEntry], // oldest first
+    pub drop_target: Option<&'a String>,
+    pub share_text: Option<&'a (String, String)>, // device_id, draft
+    pub recent_shares: &'a [String],
+    pub clipboard_history: &'a [ClipboardItem],
+    pub expanded_clipboard: Option<&'a String>,
+    pub expanded_transfers: Option<&'a String>,
+    pub phone_notifications: &'a HashMap<String, Vec<PhoneNotification>>,
+    pub notification_reply: Option<&'a (String, String, String)>, // device_id, notification id, draft
+    pub mirrored_notifications: &'a HashSet<String>,
+}
+
+pub fn create_popup_view<'a>(state: &PopupState<'a>) -> Element<'a, Message> {
     let spacing = cosmic::theme::active().cosmic().spacing;
     let mut content = widget::column().spacing(spacing.space_s).padding(spacing.space_s);
 
@@ -22,9 +56,41 @@ pub fn create_popup_view<'a>(devices: &'a HashMap<String, Device>, expanded_devi
 
     content = content.push(widget::divider::horizontal::default());
 
+    // Without the daemon there is nothing else to show
+    match state.daemon_status {
+        DaemonStatus::Running => {}
+        DaemonStatus::NotRunning => {
+            content = content.push(
+                widget::container(
+                    widget::column()
+                        .push(widget::text("KDE Connect daemon is not running").size(14))
+                        .push(
+                            widget::button::suggested("Start KDE Connect daemon")
+                                .on_press(Message::StartDaemon)
+                        )
+                        .spacing(spacing.space_xs)
+                        .align_x(Alignment::Center)
+                )
+                .padding(spacing.space_m)
+                .width(Length::Fill)
+                .center_x(Length::Fill)
+            );
+            return wrap_popup(content, &spacing);
+        }
+        DaemonStatus::Restarting => {
+            content = content.push(
+                widget::container(widget::text("Connecting to KDE Connect…").size(14))
+                    .padding(spacing.space_m)
+                    .width(Length::Fill)
+                    .center_x(Length::Fill)
+            );
+            return wrap_popup(content, &spacing);
+        }
+    }
+
     // Pairing requests - SORTED alphabetically
-    let mut pairing_requests: Vec<_> = devices.values()
-        .filter(|d| d.pairing_requests > 0 && !d.is_paired)
+    let mut pairing_requests: Vec<_> = state.devices.values()
+        .filter(|d| d.pair_state == PairState::RequestedByPeer)
         .collect();
     
     // Sort pairing requests by device name
@@ -37,27 +103,37 @@ pub fn create_popup_view<'a>(devices: &'a HashMap<String, Device>, expanded_devi
             let device_id_accept = device.id.clone();
             let device_id_reject = device.id.clone();
             
-            let request_card = widget::container(
-                widget::column()
-                    .push(
-                        widget::row()
-                            .push(widget::icon::from_name(device.device_icon()).size(24))
-                            .push(widget::text(&device.name).size(14).width(Length::Fill))
-                            .spacing(spacing.space_xs)
-                            .align_y(Alignment::Center)
-                    )
-                    .push(widget::text("wants to pair").size(12))
-                    .push(
-                        widget::row()
-                            .push(widget::button::suggested("Accept").on_press(Message::AcceptPairing(device_id_accept)))
-                            .push(widget::button::destructive("Deny").on_press(Message::RejectPairing(device_id_reject)))
-                            .spacing(spacing.space_xs)
-                    )
-                    .spacing(spacing.space_xxs)
-            )
-            .padding(spacing.space_xs)
-            .class(cosmic::theme::Container::Card)
-            .width(Length::Fill);
+            let mut request_content = widget::column()
+                .push(
+                    widget::row()
+                        .push(widget::icon::from_name(device.device_icon()).size(24))
+                        .push(widget::text(&device.name).size(14).width(Length::Fill))
+                        .spacing(spacing.space_xs)
+                        .align_y(Alignment::Center)
+                )
+                .push(widget::text("wants to pair").size(12));
+            
+            if let Some(key) = &device.verification_key {
+                request_content = request_content.push(widget::text(format!("Verification key: {}", key)).size(12));
+            }
+            
+            request_content = request_content
+                .push(
+                    widget::row()
+                        .push(widget::button::suggested("Accept").on_press(Message::AcceptPairing(device_id_accept)))
+                        .push(widget::button::destructive("Deny").on_press(Message::RejectPairing(device_id_reject)))
+                        .spacing(spacing.space_xs)
+                )
+                .spacing(spacing.space_xxs);
+            
+            if let Some(error) = state.toasts.get(&device.id) {
+                request_content = request_content.push(create_toast(&device.id, error, &spacing));
+            }
+            
+            let request_card = widget::container(request_content)
+                .padding(spacing.space_xs)
+                .class(cosmic::theme::Container::Card)
+                .width(Length::Fill);
             
             content = content.push(request_card);
         }
@@ -66,7 +142,7 @@ pub fn create_popup_view<'a>(devices: &'a HashMap<String, Device>, expanded_devi
     }
 
     // Connected devices - SORTED alphabetically
-    let mut paired_devices: Vec<_> = devices.values()
+    let mut paired_devices: Vec<_> = state.devices.values()
         .filter(|d| d.is_paired && d.is_reachable)
         .collect();
     
@@ -82,10 +158,21 @@ pub fn create_popup_view<'a>(devices: &'a HashMap<String, Device>, expanded_devi
         );
     } else {
         for device in paired_devices {
-            content = content.push(create_device_card(device, &spacing, expanded_device, expanded_player_menu));
+            let card = create_device_card(state, device, &spacing);
+            
+            // Files, folders, links and text can be dropped on devices that accept shares
+            if device.plugins.is_loaded(Plugin::Share) {
+                content = content.push(drop_destination(&device.id, card));
+            } else {
+                content = content.push(card);
+            }
         }
     }
 
+    wrap_popup(content, &spacing)
+}
+
+fn wrap_popup<'a>(content: impl Into<Element<'a, Message>>, spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
     let popup_content = widget::container(widget::scrollable(content))
         .width(Length::Fixed(400.0))
         .max_height(700.0)
@@ -98,9 +185,41 @@ pub fn create_popup_view<'a>(devices: &'a HashMap<String, Device>, expanded_devi
         .into()
 }
 
-fn create_device_card<'a>(device: &'a Device, spacing: &cosmic::cosmic_theme::Spacing, expanded_device: Option<&'a String>, expanded_player_menu: Option<&'a String>) -> Element<'a, Message> {
-    let is_expanded = expanded_device == Some(&device.id);
-    let is_player_menu_expanded = expanded_player_menu == Some(&device.id);
+fn create_device_card<'a>(state: &PopupState<'a>, device: &'a Device, spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
+    let is_expanded = state.expanded_device == Some(&device.id);
+    let is_player_menu_expanded = state.expanded_player_menu == Some(&device.id);
+    let toast = state.toasts.get(&device.id);
+    let seek_drag = state.seek_drag;
+    let virtual_display_active = state.virtual_displays.contains(&device.id);
+    let mount = state.mounts.get(&device.id);
+    let is_drop_target = state.drop_target == Some(&device.id);
+    let share_draft = state.share_text
+        .filter(|(device_id, _)| device_id == &device.id)
+        .map(|(_, draft)| draft.as_str());
+    let recent_shares = state.recent_shares;
+    let has_clipboard_history = !state.clipboard_history.is_empty();
+    let clipboard_history: &[ClipboardItem] = if state.expanded_clipboard == Some(&device.id) { state.clipboard_history } else { &[] };
+    let notifications = state.phone_notifications.get(&device.id).map(Vec::as_slice).unwrap_or_default();
+    let reply_draft = state.notification_reply
+        .filter(|(device_id, _, _)| device_id == &device.id)
+        .map(|(_, id, draft)| (id.as_str(), draft.as_str()));
+    
+    let mut transfers: Vec<_> = state.active_transfers.values()
+        .filter(|(device_id, _)| device_id == &device.id)
+        .map(|(_, transfer)| transfer)
+        .collect();
+    transfers.sort_by_key(|transfer| transfer.id);
+    
+    // Newest first; the whole saved history once expanded
+    let mut recent_transfers: Vec<_> = state.transfer_history.iter()
+        .rev()
+        .filter(|entry| entry.device_id == device.id)
+        .collect();
+    let older_transfers = recent_transfers.len().saturating_sub(RECENT_TRANSFERS);
+    let all_transfers = state.expanded_transfers == Some(&device.id);
+    if !all_transfers {
+        recent_transfers.truncate(RECENT_TRANSFERS);
+    }
     
     let mut info_col = widget::column().spacing(4);
     
@@ -147,10 +266,10 @@ fn create_device_card<'a>(device: &'a Device, spacing: &cosmic::cosmic_theme::Sp
     info_col = info_col.push(name_row);
 
     // Media controls - always visible if available
-    if device.has_mpris {
+    if device.plugins.is_loaded(Plugin::MprisRemote) {
         let mut media_section = widget::column().spacing(spacing.space_xxs);
         
-        // Title and artist (if available)
+        // Album art, title and artist (if available)
         if let Some(ref media_info) = device.media_info {
             if !media_info.title.is_empty() || !media_info.artist.is_empty() {
                 let title_text = if !media_info.title.is_empty() {
@@ -159,17 +278,63 @@ fn create_device_card<'a>(device: &'a Device, spacing: &cosmic::cosmic_theme::Sp
                     "No title".to_string()
                 };
                 
-                media_section = media_section.push(
-                    widget::text(title_text)
-                        .size(12)
-                        .font(cosmic::font::semibold())
-                );
+                let mut track_col = widget::column()
+                    .push(
+                        widget::text(title_text)
+                            .size(12)
+                            .font(cosmic::font::semibold())
+                    )
+                    .spacing(2)
+                    .width(Length::Fill);
                 
                 if !media_info.artist.is_empty() {
-                    media_section = media_section.push(
+                    track_col = track_col.push(
                         widget::text(&media_info.artist).size(11)
                     );
                 }
+                
+                let art: Element<'a, Message> = match &media_info.album_art {
+                    Some(path) => widget::icon(widget::icon::from_path(path.clone())).size(40).into(),
+                    None => widget::icon::from_name("media-optical-symbolic").size(40).into(),
+                };
+                
+                media_section = media_section.push(
+                    widget::row()
+                        .push(art)
+                        .push(track_col)
+                        .spacing(spacing.space_xs)
+                        .align_y(Alignment::Center)
+                );
+            }
+            
+            // Seek bar, read-only when the player can't seek
+            if media_info.length > 0 {
+                let length = media_info.length;
+                let position = match seek_drag {
+                    Some((drag_id, position)) if drag_id == &device.id => *position,
+                    _ => media_info.current_position(),
+                };
+                
+                let progress: Element<'a, Message> = if media_info.can_seek {
+                    widget::slider(0.0..=length as f32, position as f32, {
+                        let device_id = device.id.clone();
+                        move |value| Message::MediaSeekDragged(device_id.clone(), value as i64)
+                    })
+                    .on_release(Message::MediaSeekReleased(device.id.clone()))
+                    .width(Length::Fill)
+                    .into()
+                } else {
+                    widget::progress_bar(0.0..=length as f32, position as f32).into()
+                };
+                
+                media_section = media_section.push(
+                    widget::row()
+                        .push(widget::text(format_time(position)).size(11))
+                        .push(progress)
+                        .push(widget::text(format_time(length)).size(11))
+                        .spacing(spacing.space_xxs)
+                        .align_y(Alignment::Center)
+                );
             }
         }
         
@@ -281,15 +446,35 @@ fn create_device_card<'a>(device: &'a Device, spacing: &cosmic::cosmic_theme::Sp
         .push(info_col)
         .spacing(spacing.space_xs);
 
+    // Last failed action, until it times out or is dismissed
+    if let Some(error) = toast {
+        device_content = device_content.push(create_toast(&device.id, error, spacing));
+    }
+
+    if is_drop_target {
+        device_content = device_content.push(
+            widget::row()
+                .push(widget::icon::from_name("document-send-symbolic").size(16))
+                .push(widget::text(format!("Drop to send to {}", device.name)).size(12))
+                .spacing(spacing.space_xs)
+                .align_y(Alignment::Center)
+        );
+    }
+
+    // Transfers in progress - always visible
+    for transfer in &transfers {
+        device_content = device_content.push(create_transfer_row(*transfer, spacing));
+    }
+
     // Only show menu items if expanded
     if is_expanded {
         let mut menu_items = widget::column().spacing(spacing.space_xxs);
 
         // Communication section
-        if device.has_ping || device.has_findmyphone || device.has_sms || device.has_clipboard {
+        if device.plugins.any_loaded(&[Plugin::Ping, Plugin::FindMyPhone, Plugin::Sms, Plugin::Clipboard]) {
             menu_items = menu_items.push(widget::text("Communication").size(12).font(cosmic::font::bold()));
             
-            if device.has_ping {
+            if device.plugins.is_loaded(Plugin::Ping) {
                 menu_items = menu_items.push(
                     widget::button::text("Ping")
                         .on_press(Message::PingDevice(device.id.clone()))
@@ -298,7 +483,7 @@ fn create_device_card<'a>(device: &'a Device, spacing: &cosmic::cosmic_theme::Sp
                 );
             }
             
-            if device.has_findmyphone {
+            if device.plugins.is_loaded(Plugin::FindMyPhone) {
                 menu_items = menu_items.push(
                     widget::button::text("Ring device")
                         .on_press(Message::RingDevice(device.id.clone()))
@@ -307,7 +492,7 @@ fn create_device_card<'a>(device: &'a Device, spacing: &cosmic::cosmic_theme::Sp
                 );
             }
             
-            if device.has_sms {
+            if device.plugins.is_loaded(Plugin::Sms) {
                 menu_items = menu_items.push(
                     widget::button::text("SMS Chat")
                         .on_press(Message::SendSMS(device.id.clone()))
@@ -316,63 +501,186 @@ fn create_device_card<'a>(device: &'a Device, spacing: &cosmic::cosmic_theme::Sp
                 );
             }
             
-            if device.has_clipboard {
+            if device.plugins.is_loaded(Plugin::Clipboard) {
                 menu_items = menu_items.push(
                     widget::button::text("Share clipboard")
                         .on_press(Message::ShareClipboard(device.id.clone()))
                         .width(Length::Fill)
                         .class(cosmic::theme::Button::Text)
                 );
+                
+                if has_clipboard_history {
+                    let arrow = if clipboard_history.is_empty() { "go-down-symbolic" } else { "go-up-symbolic" };
+                    menu_items = menu_items.push(
+                        widget::button::custom(
+                            widget::row()
+                                .push(widget::text("Clipboard history").width(Length::Fill))
+                                .push(widget::icon::from_name(arrow).size(16))
+                                .align_y(Alignment::Center)
+                        )
+                        .on_press(Message::ToggleClipboardHistory(device.id.clone()))
+                        .width(Length::Fill)
+                        .class(cosmic::theme::Button::Text)
+                    );
+                    
+                    for item in clipboard_history {
+                        menu_items = menu_items.push(create_clipboard_row(&device.id, item, spacing));
+                    }
+                }
+            }
+        }
+
+        // Notifications section
+        if device.plugins.is_loaded(Plugin::Notifications) {
+            menu_items = menu_items.push(widget::divider::horizontal::light());
+            menu_items = menu_items.push(widget::text("Notifications").size(12).font(cosmic::font::bold()));
+            
+            let device_id = device.id.clone();
+            menu_items = menu_items.push(
+                widget::row()
+                    .push(widget::text("Show as desktop notifications").size(12).width(Length::Fill))
+                    .push(
+                        widget::toggler(state.mirrored_notifications.contains(&device.id))
+                            .on_toggle(move |enabled| Message::ToggleNotificationMirroring(device_id.clone(), enabled))
+                    )
+                    .spacing(spacing.space_s)
+                    .padding([0, spacing.space_xs])
+                    .align_y(cosmic::iced::Alignment::Center)
+            );
+            
+            for notification in notifications.iter().take(VISIBLE_NOTIFICATIONS) {
+                let draft = reply_draft
+                    .filter(|(id, _)| *id == notification.id)
+                    .map(|(_, draft)| draft);
+                menu_items = menu_items.push(create_notification_row(&device.id, notification, draft, spacing));
+            }
+            
+            if notifications.len() > VISIBLE_NOTIFICATIONS {
+                menu_items = menu_items.push(
+                    widget::container(
+                        widget::text(format!("{} more on the device", notifications.len() - VISIBLE_NOTIFICATIONS)).size(11)
+                    )
+                    .padding([0, spacing.space_xs])
+                );
             }
         }
 
         // File operations section
-        if device.has_share || device.has_sftp {
+        if device.plugins.any_loaded(&[Plugin::Share, Plugin::Sftp]) {
             menu_items = menu_items.push(widget::divider::horizontal::light());
             menu_items = menu_items.push(widget::text("Files").size(12).font(cosmic::font::bold()));
             
-            if device.has_share {
+            if device.plugins.is_loaded(Plugin::Share) {
                 menu_items = menu_items.push(
                     widget::button::text("Send file")
                         .on_press(Message::SendFile(device.id.clone()))
                         .width(Length::Fill)
                         .class(cosmic::theme::Button::Text)
                 );
+                menu_items = menu_items.push(
+                    widget::button::text("Send text / link")
+                        .on_press(Message::ToggleShareText(device.id.clone()))
+                        .width(Length::Fill)
+                        .class(cosmic::theme::Button::Text)
+                );
+                
+                if let Some(draft) = share_draft {
+                    menu_items = menu_items.push(create_share_text_dialog(&device.id, draft, recent_shares, spacing));
+                }
             }
             
             // Browse device functionality
-            if device.has_sftp {
+            if device.plugins.is_loaded(Plugin::Sftp) {
+                let is_mounting = mount == Some(&MountState::Mounting);
                 menu_items = menu_items.push(
                     widget::button::text("Browse this device")
-                        .on_press(Message::BrowseDevice(device.id.clone()))
+                        .on_press_maybe((!is_mounting).then(|| Message::BrowseDevice(device.id.clone())))
                         .width(Length::Fill)
                         .class(cosmic::theme::Button::Text)
                 );
+                
+                if let Some(mount) = mount {
+                    menu_items = menu_items.push(create_mount_status(&device.id, mount, spacing));
+                }
             }
         }
 
-        // Security & Display section (Lock device and Use as monitor only)
-        if device.has_lockdevice || device.has_virtualmonitor {
+        // Transfer history section
+        if !recent_transfers.is_empty() {
             menu_items = menu_items.push(widget::divider::horizontal::light());
-            menu_items = menu_items.push(widget::text("Security & Display").size(12).font(cosmic::font::bold()));
+            menu_items = menu_items.push(widget::text("Recent transfers").size(12).font(cosmic::font::bold()));
             
-            if device.has_lockdevice {
+            for entry in &recent_transfers {
+                menu_items = menu_items.push(create_history_row(*entry, spacing));
+            }
+            
+            if older_transfers > 0 {
+                let label = if all_transfers {
+                    "Show fewer".to_string()
+                } else {
+                    format!("Show all ({} more)", older_transfers)
+                };
                 menu_items = menu_items.push(
-                    widget::button::text("Lock device")
-                        .on_press(Message::LockDevice(device.id.clone()))
+                    widget::button::text(label)
+                        .on_press(Message::ToggleTransferHistory(device.id.clone()))
                         .width(Length::Fill)
                         .class(cosmic::theme::Button::Text)
                 );
             }
+        }
+
+        // Remote input section
+        if device.plugins.any_loaded(&[Plugin::RemoteKeyboard, Plugin::RemoteControl]) {
+            menu_items = menu_items.push(widget::divider::horizontal::light());
+            menu_items = menu_items.push(widget::text("Control").size(12).font(cosmic::font::bold()));
+            
+            menu_items = menu_items.push(
+                widget::button::text("Remote input")
+                    .on_press(Message::RemoteInput(device.id.clone()))
+                    .width(Length::Fill)
+                    .class(cosmic::theme::Button::Text)
+            );
+        }
+
+        // Security & Display section (Lock device and Use as monitor only)
+        if device.plugins.any_loaded(&[Plugin::LockDevice, Plugin::VirtualMonitor]) {
+            menu_items = menu_items.push(widget::divider::horizontal::light());
+            menu_items = menu_items.push(widget::text("Security & Display").size(12).font(cosmic::font::bold()));
             
-            if device.has_virtualmonitor {
+            if device.plugins.is_loaded(Plugin::LockDevice) {
                 menu_items = menu_items.push(
-                    widget::button::text("Use as monitor")
-                        .on_press(Message::UseAsMonitor(device.id.clone()))
+                    widget::button::text("Lock device")
+                        .on_press(Message::LockDevice(device.id.clone()))
                         .width(Length::Fill)
                         .class(cosmic::theme::Button::Text)
                 );
             }
+            
+            if device.plugins.is_loaded(Plugin::VirtualMonitor) {
+                if virtual_display_active {
+                    menu_items = menu_items.push(
+                        widget::row()
+                            .push(widget::icon::from_name("video-display-symbolic").size(16))
+                            .push(widget::text("Virtual display active").size(12))
+                            .spacing(spacing.space_xs)
+                            .align_y(Alignment::Center)
+                            .padding([0, spacing.space_xs])
+                    );
+                    menu_items = menu_items.push(
+                        widget::button::text("Stop virtual display")
+                            .on_press(Message::StopMonitor(device.id.clone()))
+                            .width(Length::Fill)
+                            .class(cosmic::theme::Button::Text)
+                    );
+                } else {
+                    menu_items = menu_items.push(
+                        widget::button::text("Use as monitor")
+                            .on_press(Message::UseAsMonitor(device.id.clone()))
+                            .width(Length::Fill)
+                            .class(cosmic::theme::Button::Text)
+                    );
+                }
+            }
         }
 
         device_content = device_content.push(menu_items);
@@ -382,4 +690,333 @@ fn create_device_card<'a>(device: &'a Device, spacing: &cosmic::cosmic_theme::Sp
         .class(cosmic::theme::Container::Card)
         .width(Length::Fill)
         .into()
-}
\ No newline at end of file
+}
+
+fn create_toast<'a>(device_id: &str, error: &'a str, spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
+    widget::row()
+        .push(widget::icon::from_name("dialog-warning-symbolic").size(16))
+        .push(widget::text(error).size(12).width(Length::Fill))
+        .push(
+            widget::button::icon(widget::icon::from_name("window-close-symbolic").size(14))
+                .on_press(Message::DismissToast(device_id.to_string()))
+        )
+        .spacing(spacing.space_xs)
+        .align_y(Alignment::Center)
+        .into()
+}
+
+fn drop_destination<'a>(device_id: &str, card: Element<'a, Message>) -> Element<'a, Message> {
+    let on_drop = device_id.to_string();
+    let on_enter = device_id.to_string();
+    
+    widget::dnd_destination::DndDestination::for_data::<DroppedItems>(card, move |items, _action| match items {
+        Some(items) => Message::ItemsDropped(on_drop.clone(), items),
+        None => Message::DropTargetChanged(None),
+    })
+    .on_enter(move |_, _, _| Message::DropTargetChanged(Some(on_enter.clone())))
+    .on_leave(|| Message::DropTargetChanged(None))
+    .into()
+}
+
+/// Where the device's storage is mounted, or why it isn't
+fn create_mount_status<'a>(device_id: &str, mount: &'a MountState, spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
+    let (icon, status, can_unmount) = match mount {
+        MountState::Unmounted => ("drive-harddisk-symbolic", "Not mounted".to_string(), false),
+        MountState::Mounting => ("content-loading-symbolic", "Mounting...".to_string(), false),
+        MountState::Mounted(path) => ("drive-harddisk-symbolic", format!("Mounted at {}", path.display()), true),
+        MountState::Stale => ("dialog-warning-symbolic", "Connection to the mounted storage was lost".to_string(), true),
+        MountState::Failed(reason) => ("dialog-error-symbolic", format!("Mount failed: {}", reason), false),
+    };
+    
+    let mut row = widget::row()
+        .push(widget::icon::from_name(icon).size(16))
+        .push(widget::text(status).size(12).width(Length::Fill))
+        .spacing(spacing.space_xs)
+        .align_y(Alignment::Center)
+        .padding([0, spacing.space_xs]);
+    
+    if can_unmount {
+        row = row.push(
+            widget::button::text("Unmount")
+                .on_press(Message::UnmountDevice(device_id.to_string()))
+        );
+    }
+    
+    row.into()
+}
+
+fn create_transfer_row<'a>(transfer: &'a Transfer, spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
+    let status = if transfer.total_bytes > 0 {
+        format!("{} of {}", format_size(transfer.processed_bytes), format_size(transfer.total_bytes))
+    } else {
+        "Starting...".to_string()
+    };
+    
+    let mut row = widget::row()
+        .push(widget::icon::from_name(direction_icon(transfer.direction())).size(16))
+        .push(
+            widget::column()
+                .push(widget::text(transfer.file_name()).size(12))
+                .push(widget::progress_bar(0.0..=1.0, transfer.progress()).height(Length::Fixed(4.0)))
+                .push(widget::text(status).size(10))
+                .spacing(spacing.space_xxxs)
+                .width(Length::Fill)
+        )
+        .spacing(spacing.space_xs)
+        .align_y(Alignment::Center);
+    
+    if transfer.can_cancel && transfer.state == TransferState::Running {
+        row = row.push(
+            widget::button::icon(widget::icon::from_name("process-stop-symbolic").size(14))
+                .on_press(Message::CancelTransfer(transfer.id))
+        );
+    }
+    
+    row.into()
+}
+
+fn create_history_row<'a>(entry: &'a HistoryEntry, spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
+    let status = if entry.succeeded {
+        format!("{} - {}", format_size(entry.size), format_age(entry.timestamp))
+    } else {
+        format!("Failed - {}", format_age(entry.timestamp))
+    };
+    
+    let mut row = widget::row()
+        .push(widget::icon::from_name(direction_icon(entry.direction)).size(16))
+        .push(
+            widget::column()
+                .push(widget::text(&entry.file_name).size(12))
+                .push(widget::text(status).size(10))
+                .spacing(spacing.space_xxxs)
+                .width(Length::Fill)
+        )
+        .spacing(spacing.space_xs)
+        .align_y(Alignment::Center);
+    
+    if let Some(path) = entry.path.as_ref().filter(|_| entry.succeeded) {
+        row = row
+            .push(
+                widget::button::icon(widget::icon::from_name("document-open-symbolic").size(14))
+                    .on_press(Message::OpenTransferFile(entry.device_id.clone(), path.clone()))
+            )
+            .push(
+                widget::button::icon(widget::icon::from_name("system-run-symbolic").size(14))
+                    .on_press(Message::OpenTransferWith(entry.device_id.clone(), path.clone()))
+            )
+            .push(
+                widget::button::icon(widget::icon::from_name("folder-open-symbolic").size(14))
+                    .on_press(Message::OpenTransferFolder(entry.device_id.clone(), path.clone()))
+            );
+    }
+    
+    row.into()
+}
+
+/// Text field for something to send, with recently sent items to send again
+fn create_share_text_dialog<'a>(device_id: &str, draft: &'a str, recent_shares: &'a [String], spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
+    let send_label = if shares::link(draft).is_some() { "Open on device" } else { "Send" };
+    let mut send = widget::button::suggested(send_label);
+    if !draft.trim().is_empty() {
+        send = send.on_press(Message::SendShareText);
+    }
+    
+    let mut dialog = widget::column()
+        .push(
+            widget::text_input("Text or link", draft)
+                .on_input(Message::ShareTextChanged)
+                .on_submit(|_| Message::SendShareText)
+                .width(Length::Fill)
+        )
+        .push(
+            widget::row()
+                .push(widget::horizontal_space())
+                .push(widget::button::standard("Cancel").on_press(Message::ToggleShareText(device_id.to_string())))
+                .push(send)
+                .spacing(spacing.space_xs)
+        )
+        .spacing(spacing.space_xs)
+        .padding([spacing.space_xxs, spacing.space_xs]);
+    
+    if !recent_shares.is_empty() {
+        dialog = dialog.push(widget::text("Recently sent").size(12));
+    }
+    for content in recent_shares {
+        let (icon, message) = match shares::link(content) {
+            Some(url) => ("insert-link-symbolic", Message::ShareUrl(device_id.to_string(), url.to_string())),
+            None => ("edit-paste-symbolic", Message::ShareText(device_id.to_string(), content.clone())),
+        };
+        dialog = dialog.push(
+            widget::button::custom(
+                widget::row()
+                    .push(widget::icon::from_name(icon).size(16))
+                    .push(widget::text(preview(content)).size(12))
+                    .spacing(spacing.space_xs)
+                    .align_y(Alignment::Center)
+            )
+            .on_press(message)
+            .width(Length::Fill)
+            .class(cosmic::theme::Button::Text)
+        );
+    }
+    
+    dialog.into()
+}
+
+fn create_clipboard_row<'a>(device_id: &str, item: &'a ClipboardItem, spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
+    widget::row()
+        .push(
+            widget::column()
+                .push(widget::text(preview(&item.text)).size(12))
+                .push(widget::text(format_age(item.copied_at)).size(10))
+                .spacing(spacing.space_xxxs)
+                .width(Length::Fill)
+        )
+        .push(
+            widget::button::icon(widget::icon::from_name("document-send-symbolic").size(14))
+                .on_press(Message::SendClipboardItem(device_id.to_string(), item.clone()))
+        )
+        .push(
+            widget::button::icon(widget::icon::from_name("edit-copy-symbolic").size(14))
+                .on_press(Message::CopyClipboardItem(item.clone()))
+        )
+        .spacing(spacing.space_xs)
+        .align_y(Alignment::Center)
+        .padding([0, spacing.space_xs])
+        .into()
+}
+
+/// A notification shown on the phone, with its action buttons and, once
+/// opened, a reply field
+fn create_notification_row<'a>(device_id: &str, notification: &'a PhoneNotification, reply_draft: Option<&'a str>, spacing: &cosmic::cosmic_theme::Spacing) -> Element<'a, Message> {
+    let icon: Element<'a, Message> = match &notification.icon_path {
+        Some(path) => widget::icon(widget::icon::from_path(path.clone())).size(24).into(),
+        None => widget::icon::from_name("preferences-system-notifications-symbolic").size(24).into(),
+    };
+    
+    let heading = if notification.title.is_empty() {
+        notification.app_name.clone()
+    } else {
+        format!("{}: {}", notification.app_name, notification.title)
+    };
+    let mut text_col = widget::column()
+        .push(widget::text(preview(&heading)).size(12).font(cosmic::font::semibold()))
+        .spacing(spacing.space_xxxs)
+        .width(Length::Fill);
+    if !notification.text.is_empty() {
+        text_col = text_col.push(widget::text(preview(&notification.text)).size(11));
+    }
+    
+    let mut header = widget::row()
+        .push(icon)
+        .push(text_col)
+        .spacing(spacing.space_xs)
+        .align_y(Alignment::Center);
+    if notification.can_reply {
+        header = header.push(
+            widget::button::icon(widget::icon::from_name("mail-reply-sender-symbolic").size(14))
+                .on_press(Message::ToggleNotificationReply(device_id.to_string(), notification.id.clone()))
+        );
+    }
+    if notification.dismissable {
+        header = header.push(
+            widget::button::icon(widget::icon::from_name("window-close-symbolic").size(14))
+                .on_press(Message::DismissNotification(device_id.to_string(), notification.id.clone()))
+        );
+    }
+    
+    let mut row = widget::column()
+        .push(header)
+        .spacing(spacing.space_xxs)
+        .padding([0, spacing.space_xs]);
+    
+    if !notification.actions.is_empty() {
+        let mut actions = widget::row().spacing(spacing.space_xxs);
+        for action in &notification.actions {
+            actions = actions.push(
+                widget::button::text(action.as_str())
+                    .on_press(Message::NotificationAction(device_id.to_string(), notification.key.clone(), action.clone()))
+            );
+        }
+        row = row.push(actions);
+    }
+    
+    if let Some(draft) = reply_draft {
+        let mut send = widget::button::suggested("Send");
+        if !draft.trim().is_empty() {
+            send = send.on_press(Message::SendNotificationReply);
+        }
+        row = row.push(
+            widget::row()
+                .push(
+                    widget::text_input("Reply", draft)
+                        .on_input(Message::NotificationReplyChanged)
+                        .on_submit(|_| Message::SendNotificationReply)
+                        .width(Length::Fill)
+                )
+                .push(send)
+                .spacing(spacing.space_xs)
+                .align_y(Alignment::Center)
+        );
+    }
+    
+    row.into()
+}
+
+/// First line of sent content, shortened to fit the card
+fn preview(content: &str) -> String {
+    const MAX_CHARS: usize = 40;
+    let line = content.trim().lines().next().unwrap_or_default();
+    if line.chars().count() > MAX_CHARS || line.len() < content.trim().len() {
+        format!("{}…", line.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+fn direction_icon(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Incoming => "go-down-symbolic",
+        Direction::Outgoing => "go-up-symbolic",
+    }
+}
+
+/// Format a byte count with a binary unit, e.g. `1.5 MB`
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// How long ago a Unix timestamp was, e.g. `5 min ago`
+fn format_age(timestamp: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let seconds = now.saturating_sub(timestamp);
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+/// Format milliseconds as `m:ss`, or `h:mm:ss` for long tracks
+fn format_time(ms: i64) -> String {
+    let seconds = ms.max(0) / 1000;
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}